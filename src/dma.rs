@@ -27,6 +27,22 @@
 //!    ROM/FlashROM. Do note that it cannot write to game pak SRAM. This must
 //!    always be done by the processor.
 
+use crate::InterruptFlagBits;
+
+const_enum! {
+  /// One of the four DMA channels.
+  DmaChannel(u8) {
+    /// DMA0, the highest priority channel.
+    Dma0(0),
+    /// DMA1, usually used for a sound FIFO.
+    Dma1(1),
+    /// DMA2, usually used for a sound FIFO.
+    Dma2(2),
+    /// DMA3, the general purpose channel.
+    Dma3(3),
+  }
+}
+
 const_enum! {
   /// Destination control settings.
   DmaDestinationAddressControl(u16) {
@@ -45,11 +61,11 @@ const_enum! {
   /// Source control settings.
   DmaSourceAddressControl(u16) {
     /// Increment the address with each copy.
-    Increment(0),
+    Increment(0 << 7),
     /// Decrement the address with each copy.
-    Decrement(1),
+    Decrement(1 << 7),
     /// Do not move.
-    Fixed(2),
+    Fixed(2 << 7),
   }
 }
 
@@ -59,11 +75,11 @@ const_enum! {
     /// Starts the DMA 2 cycles after setting the enable bit in the control
     /// register. The processor will be halted during these two cycles, so you
     /// don't need to worry about messing up the DMA settings.
-    Immediate(0),
+    Immediate(0 << 12),
     /// Start the DMA on a vblank interrupt.
-    Vblank(1),
+    Vblank(1 << 12),
     /// Start the DMA on an hblank interrupt.
-    Hblank(2),
+    Hblank(2 << 12),
     /// Start time depends on the DMA used.
     ///
     /// DMA0: prohibited. Do not use.
@@ -71,7 +87,7 @@ const_enum! {
     /// DMA3: Video Capture
     /// ## Safety
     /// * This value is prohibited for DMA0
-    Special(3),
+    Special(3 << 12),
   }
 }
 
@@ -84,14 +100,427 @@ bitstruct_newtype! {
     [7-8 => DmaSourceAddressControl: src_addr_control, set_src_addr_control],
     /// If cleared to 0, then the enabled bit (15) will be cleared as well when DMA is complete.
     /// If set to 1, then the enable bit will remain set and the DMA will repeat when its start event happens again.
-    [9: repeating, set_repeating],
+    [9: repeating, set_repeating, toggle_repeating],
     /// Set to 1 to preform a transfer at 32bits at a time. Clear to 0 to transfer 16bits at a time.
-    [10: transfer32, set_transfer32],
+    [10: transfer32, set_transfer32, toggle_transfer32],
     /// Set the event to trigger the DMA.
     [12-13 => DmaStartTiming: start_timing, set_start_timing],
     /// Set to 1 to trigger an interrupt when complete.
-    [14: interrupt_when_complete, set_interrupt_when_complete],
+    [14: interrupt_when_complete, set_interrupt_when_complete, toggle_interrupt_when_complete],
     /// Set to 1 to enable.
-    [15: enabled, set_enabled],
+    [15: enabled, set_enabled, toggle_enabled],
+  }
+}
+
+impl DmaControlSetting {
+  /// The settings for feeding a sound FIFO.
+  ///
+  /// This is a repeating 32-bit transfer with an incrementing source, a fixed
+  /// destination (the FIFO address), and special start timing. It's only valid
+  /// for DMA1 and DMA2, which start a transfer whenever their FIFO runs low.
+  ///
+  /// The DMA still needs to be enabled before it will run.
+  #[inline]
+  #[must_use]
+  pub const fn sound_fifo() -> Self {
+    let mut control = Self(0);
+    control.set_dst_addr_control(DmaDestinationAddressControl::Fixed);
+    control.set_src_addr_control(DmaSourceAddressControl::Increment);
+    control.set_repeating(true);
+    control.set_transfer32(true);
+    control.set_start_timing(DmaStartTiming::Special);
+    control
+  }
+
+  /// The settings for video capture.
+  ///
+  /// This is a repeating transfer with incrementing source and destination
+  /// addresses and special start timing. It's only valid for DMA3.
+  ///
+  /// In video capture mode DMA3 runs one transfer per scanline, starting at
+  /// line 2 and ending at line 161, which is when the camera hardware has a
+  /// line of pixels ready. The word count should be one line's worth of data.
+  /// After the last line the hardware clears the enable bit on its own, so
+  /// the DMA must be set up again for each frame.
+  ///
+  /// The transfer width is left at 16 bits, and the DMA still needs to be
+  /// enabled before it will run.
+  #[inline]
+  #[must_use]
+  pub const fn video_capture() -> Self {
+    let mut control = Self(0);
+    control.set_dst_addr_control(DmaDestinationAddressControl::Increment);
+    control.set_src_addr_control(DmaSourceAddressControl::Increment);
+    control.set_repeating(true);
+    control.set_start_timing(DmaStartTiming::Special);
+    control
+  }
+
+  /// The number of bytes moved by each unit of the transfer, either 2 or 4.
+  #[inline]
+  #[must_use]
+  pub const fn transfer_width_bytes(self) -> usize {
+    if self.transfer32() {
+      4
+    } else {
+      2
+    }
+  }
+}
+
+bitstruct_newtype! {
+  /// The number of units a DMA channel should transfer.
+  ///
+  /// Each unit is 16 or 32 bits depending on the control setting. DMA0, DMA1,
+  /// and DMA2 only use the lower 14 bits. Writing 0 gives the maximum count,
+  /// which is 0x4000 for DMA0-2 and 0x10000 for DMA3.
+  DmaWordCount(u16) {
+    /// The number of units to transfer.
+    [0-15: count, set_count],
+  }
+}
+
+/// The `IF` bit that a DMA channel sets when it completes.
+///
+/// The channel only raises this interrupt if it has `interrupt_when_complete`
+/// set.
+#[inline]
+#[must_use]
+pub const fn completion_interrupt(channel: DmaChannel) -> InterruptFlagBits {
+  let mut bits = InterruptFlagBits(0);
+  match channel {
+    DmaChannel::Dma0 => bits.set_dma0(true),
+    DmaChannel::Dma1 => bits.set_dma1(true),
+    DmaChannel::Dma2 => bits.set_dma2(true),
+    _ => bits.set_dma3(true),
+  }
+  bits
+}
+
+/// The registers of one DMA channel, as byte offsets from the start of the IO
+/// register region, 0x04000000.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DmaRegisterOffsets {
+  /// The source address register, `DMAxSAD`.
+  pub sad: usize,
+  /// The destination address register, `DMAxDAD`.
+  pub dad: usize,
+  /// The word count register, `DMAxCNT_L`. See [`DmaWordCount`].
+  pub cnt_l: usize,
+  /// The control register, `DMAxCNT_H`. See [`DmaControlSetting`].
+  pub cnt_h: usize,
+}
+
+/// The register offsets of a DMA channel.
+///
+/// Each channel's registers take 12 bytes, starting with DMA0 at 0xB0.
+#[inline]
+#[must_use]
+pub const fn dma_register_offsets(channel: DmaChannel) -> DmaRegisterOffsets {
+  let base = 0xB0 + 12 * (channel.0 as usize & 0b11);
+  DmaRegisterOffsets {
+    sad: base,
+    dad: base + 4,
+    cnt_l: base + 8,
+    cnt_h: base + 10,
+  }
+}
+
+/// A reason that a DMA's settings and addresses won't work together, found by
+/// [`validate_addresses`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DmaAddrError {
+  /// The source address control is the prohibited value 3. That would be
+  /// `IncrementReload` for a destination, but sources can't reload.
+  ProhibitedSourceControl,
+  /// The start timing is `Special`, which is prohibited on DMA0.
+  ProhibitedStartTiming,
+  /// The channel can't read from the source address.
+  SourceNotReadable,
+  /// The channel can't write to the destination address.
+  DestinationNotWritable,
+  /// The source is in the game pak but its address control isn't
+  /// `Increment`. Game pak reads always increment, so a decrementing or fixed
+  /// source doesn't read what it says.
+  GamepakSourceNotIncrementing,
+}
+
+/// Checks the documented address restrictions of a DMA channel.
+///
+/// DMA0 can only access internal memory, DMA1 and DMA2 can also read game pak
+/// ROM, and DMA3 can also write to game pak ROM. No channel can access game
+/// pak SRAM. Only the start addresses are checked, not where the transfer
+/// ends up.
+///
+/// The address controls are also checked: the source control can't be the
+/// prohibited value, and a game pak source has to increment.
+pub fn validate_addresses(
+  control: DmaControlSetting, channel: DmaChannel, src: usize, dst: usize,
+) -> Result<(), DmaAddrError> {
+  const GAMEPAK_START: usize = 0x0800_0000;
+  const SRAM_START: usize = 0x0E00_0000;
+  if !DmaSourceAddressControl::ALL.contains(&control.src_addr_control()) {
+    return Err(DmaAddrError::ProhibitedSourceControl);
+  }
+  if channel == DmaChannel::Dma0
+    && control.start_timing() == DmaStartTiming::Special
+  {
+    return Err(DmaAddrError::ProhibitedStartTiming);
+  }
+  let src_limit = match channel {
+    DmaChannel::Dma0 => GAMEPAK_START,
+    _ => SRAM_START,
+  };
+  if src >= src_limit {
+    return Err(DmaAddrError::SourceNotReadable);
+  }
+  if src >= GAMEPAK_START
+    && control.src_addr_control() != DmaSourceAddressControl::Increment
+  {
+    return Err(DmaAddrError::GamepakSourceNotIncrementing);
+  }
+  let dst_limit = match channel {
+    DmaChannel::Dma3 => SRAM_START,
+    _ => GAMEPAK_START,
+  };
+  if dst >= dst_limit {
+    return Err(DmaAddrError::DestinationNotWritable);
+  }
+  Ok(())
+}
+
+/// The total number of bytes a DMA will move.
+///
+/// A count of 0 is the channel's maximum count on hardware, but since that
+/// depends on the channel this treats it as 0.
+#[inline]
+#[must_use]
+pub const fn total_bytes(
+  count: DmaWordCount, control: DmaControlSetting,
+) -> usize {
+  count.count() as usize * control.transfer_width_bytes()
+}
+
+/// A rough estimate of how many CPU cycles a DMA transfer takes.
+///
+/// This assumes both addresses are in fast internal memory such as IWRAM,
+/// palette RAM, VRAM, or OAM. Each 16-bit unit then takes a read and a write
+/// of 1 cycle each. Palette RAM, VRAM, and OAM have a 16-bit bus, so each
+/// 32-bit unit takes 4 cycles instead. There are also 4 cycles of setup.
+/// Transfers involving EWRAM or the game pak take longer because of their
+/// wait states.
+///
+/// A `word_count` too large for the hardware saturates at `u32::MAX` instead
+/// of overflowing.
+#[inline]
+#[must_use]
+pub const fn estimated_cycles(
+  control: DmaControlSetting, word_count: u32,
+) -> u32 {
+  let width = control.transfer_width_bytes() as u32;
+  word_count.saturating_mul(width).saturating_add(4)
+}
+
+/// The number of CPU cycles in one HBlank period: 68 dots of 4 cycles each.
+const HBLANK_CYCLES: u32 = 272;
+
+/// If a DMA transfer should finish within a single HBlank, based on
+/// [`estimated_cycles`].
+///
+/// Since the estimate assumes fast memory this is the best case, so leave
+/// some room when the transfer is close to the limit.
+#[inline]
+#[must_use]
+pub const fn fits_in_hblank(
+  control: DmaControlSetting, word_count: u32,
+) -> bool {
+  estimated_cycles(control, word_count) <= HBLANK_CYCLES
+}
+
+#[cfg(feature = "alloc")]
+impl DmaControlSetting {
+  /// A short human readable description of the settings, for debugging.
+  ///
+  /// For example: "32-bit copy, src increment, dst fixed, start on VBlank, IRQ
+  /// on complete".
+  #[must_use]
+  pub fn describe(self) -> alloc::string::String {
+    let width = if self.transfer32() { "32-bit" } else { "16-bit" };
+    let src = match self.src_addr_control() {
+      DmaSourceAddressControl::Increment => "increment",
+      DmaSourceAddressControl::Decrement => "decrement",
+      DmaSourceAddressControl::Fixed => "fixed",
+      _ => "prohibited",
+    };
+    let dst = match self.dst_addr_control() {
+      DmaDestinationAddressControl::Increment => "increment",
+      DmaDestinationAddressControl::Decrement => "decrement",
+      DmaDestinationAddressControl::Fixed => "fixed",
+      _ => "increment/reload",
+    };
+    let start = match self.start_timing() {
+      DmaStartTiming::Immediate => "immediately",
+      DmaStartTiming::Vblank => "on VBlank",
+      DmaStartTiming::Hblank => "on HBlank",
+      _ => "on special",
+    };
+    let mut description = alloc::format!(
+      "{} copy, src {}, dst {}, start {}",
+      width,
+      src,
+      dst,
+      start
+    );
+    if self.repeating() {
+      description.push_str(", repeating");
+    }
+    if self.interrupt_when_complete() {
+      description.push_str(", IRQ on complete");
+    }
+    description
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn positioned_fields_round_trip() {
+    let mut control = DmaControlSetting::default();
+    for &src in DmaSourceAddressControl::ALL {
+      control.set_src_addr_control(src);
+      assert_eq!(control.src_addr_control(), src);
+    }
+    for &start in DmaStartTiming::ALL {
+      control.set_start_timing(start);
+      assert_eq!(control.start_timing(), start);
+    }
+    assert_eq!(control.0, 2 << 7 | 3 << 12);
+    control.set_start_timing(DmaStartTiming::Vblank);
+    assert_eq!(control.0, 2 << 7 | 1 << 12);
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn describe_known_control() {
+    let mut control = DmaControlSetting::default();
+    control.set_transfer32(true);
+    control.set_dst_addr_control(DmaDestinationAddressControl::Fixed);
+    control.set_start_timing(DmaStartTiming::Vblank);
+    control.set_interrupt_when_complete(true);
+    assert_eq!(
+      control.describe(),
+      "32-bit copy, src increment, dst fixed, start on VBlank, IRQ on complete"
+    );
+    assert_eq!(
+      DmaControlSetting::sound_fifo().describe(),
+      "32-bit copy, src increment, dst fixed, start on special, repeating"
+    );
+  }
+
+  #[test]
+  fn sound_fifo_control_word() {
+    let control = DmaControlSetting::sound_fifo();
+    assert_eq!(control.0, 0x3640);
+    assert!(!control.enabled());
+  }
+
+  #[test]
+  fn total_bytes_uses_transfer_width() {
+    let count = DmaWordCount(100);
+    let mut control = DmaControlSetting::default();
+    assert_eq!(total_bytes(count, control), 200);
+    control.set_transfer32(true);
+    assert_eq!(total_bytes(count, control), 400);
+    assert_eq!(total_bytes(DmaWordCount(0), control), 0);
+  }
+
+  #[test]
+  fn video_capture_control_word() {
+    let control = DmaControlSetting::video_capture();
+    assert_eq!(control.0, 0x3200);
+    assert_eq!(control.transfer_width_bytes(), 2);
+  }
+
+  #[test]
+  fn completion_interrupt_bits() {
+    assert_eq!(completion_interrupt(DmaChannel::Dma0).0, 1 << 8);
+    assert_eq!(completion_interrupt(DmaChannel::Dma1).0, 1 << 9);
+    assert_eq!(completion_interrupt(DmaChannel::Dma2).0, 1 << 10);
+    let dma3 = completion_interrupt(DmaChannel::Dma3);
+    assert!(dma3.dma3());
+    assert_eq!(dma3.0, 1 << 11);
+  }
+
+  #[test]
+  fn validate_channel_addresses() {
+    const IWRAM: usize = 0x0300_0000;
+    const VRAM: usize = 0x0600_0000;
+    const ROM: usize = 0x0800_0000;
+    let control = DmaControlSetting::default();
+    assert_eq!(
+      validate_addresses(control, DmaChannel::Dma0, ROM, VRAM),
+      Err(DmaAddrError::SourceNotReadable)
+    );
+    assert_eq!(
+      validate_addresses(control, DmaChannel::Dma0, IWRAM, VRAM),
+      Ok(())
+    );
+    assert_eq!(
+      validate_addresses(control, DmaChannel::Dma3, ROM, VRAM),
+      Ok(())
+    );
+    assert_eq!(
+      validate_addresses(control, DmaChannel::Dma2, IWRAM, ROM),
+      Err(DmaAddrError::DestinationNotWritable)
+    );
+    let mut decrement = control;
+    decrement.set_src_addr_control(DmaSourceAddressControl::Decrement);
+    assert_eq!(
+      validate_addresses(decrement, DmaChannel::Dma3, ROM, VRAM),
+      Err(DmaAddrError::GamepakSourceNotIncrementing)
+    );
+    assert_eq!(
+      validate_addresses(decrement, DmaChannel::Dma3, IWRAM, VRAM),
+      Ok(())
+    );
+    let reload = DmaControlSetting(3 << 7);
+    assert_eq!(
+      validate_addresses(reload, DmaChannel::Dma3, IWRAM, VRAM),
+      Err(DmaAddrError::ProhibitedSourceControl)
+    );
+    let mut special = control;
+    special.set_start_timing(DmaStartTiming::Special);
+    assert_eq!(
+      validate_addresses(special, DmaChannel::Dma0, IWRAM, VRAM),
+      Err(DmaAddrError::ProhibitedStartTiming)
+    );
+  }
+
+  #[test]
+  fn hblank_transfer_estimates() {
+    let mut control = DmaControlSetting::default();
+    control.set_transfer32(true);
+    assert_eq!(estimated_cycles(control, 60), 244);
+    assert!(fits_in_hblank(control, 60));
+    assert_eq!(estimated_cycles(control, 120), 484);
+    assert!(!fits_in_hblank(control, 120));
+    assert_eq!(estimated_cycles(control, u32::MAX), u32::MAX);
+    assert!(!fits_in_hblank(control, u32::MAX));
+  }
+
+  #[test]
+  fn dma0_and_dma3_register_offsets() {
+    let dma0 = dma_register_offsets(DmaChannel::Dma0);
+    assert_eq!(
+      dma0,
+      DmaRegisterOffsets { sad: 0xB0, dad: 0xB4, cnt_l: 0xB8, cnt_h: 0xBA }
+    );
+    let dma3 = dma_register_offsets(DmaChannel::Dma3);
+    assert_eq!(
+      dma3,
+      DmaRegisterOffsets { sad: 0xD4, dad: 0xD8, cnt_l: 0xDC, cnt_h: 0xDE }
+    );
   }
 }
@@ -23,6 +23,10 @@
 //!   readable and writable in all fields, but even so every struct has getters
 //!   and setters for all fields just to make the in-memory manipulation of a
 //!   value as easy as possible.
+//! * Single-bit fields also get a toggle method, which flips the bit.
+//! * Each struct implements the [`Register`] trait, which converts to and from
+//!   the raw integer, lists the fields, and can do volatile reads and writes of
+//!   an MMIO address.
 //!
 //! This crate also contains some helper types that are considered to be a
 //! `const_enum!` type:
@@ -34,6 +38,11 @@
 //! * Unlike with a normal enum, because this is a wrapped integer it's more FFI
 //!   friendly. If an illegal bit pattern *does* somehow get read in from a
 //!   register then it won't instantly cause UB.
+//! * The declared values are listed in order in an `ALL` const, which is used
+//!   to step between them.
+//! * A raw value, such as one read from a register, can be checked with
+//!   `from_bits_checked` or `TryFrom` of the integer type. Both fail if it
+//!   isn't one of the declared values.
 //!
 //! ## Nightly Only
 //!
@@ -42,6 +51,24 @@
 //!   probably become stable "sooner rather than later", particularly compared
 //!   to some of the other nightly features that GBA programming is likely to
 //!   use.
+//!
+//! ## Features
+//!
+//! * `alloc`: Enables helpers that return allocated values, such as the
+//!   `describe` debug helpers and [`changed_fields`]. These are intended for
+//!   debug tooling, not the hot path of a game.
+//! * `libm`: Enables constructors that take floats, such as
+//!   [`AffineParameter::from_f32`]. The rounding they need comes from the
+//!   `libm` crate.
+//! * `bytemuck`: Implements `bytemuck::Pod` and `bytemuck::Zeroable` for all of
+//!   the `bitstruct_newtype!` and `const_enum!` types, so that slices of them
+//!   can be cast to and from slices of their integer type.
+//! * `serde`: Implements `Serialize` and `Deserialize` for all of the
+//!   `bitstruct_newtype!` and `const_enum!` types. They're stored as just their
+//!   integer value.
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 macro_rules! bit_get {
   ($val:expr, $mask:expr) => {
@@ -63,8 +90,26 @@ macro_rules! bit_set {
 /// Declares a newtype with a private field and a series of named constants.
 ///
 /// This is far more FFI safe than a rust `enum` type.
+///
+/// Writing `Name(u8 as u16)` declares a `u8` type that also gets a `widen`
+/// method giving its value as a `u16`, for when it's placed in a wider
+/// register.
+///
+/// Two consts with the same value are a compile error. The macro is exported
+/// (but hidden, it's not part of the public API) so that this can be checked:
+///
+/// ```compile_fail
+/// gba_types::const_enum! {
+///   Duplicated(u8) {
+///     First(1),
+///     Second(1),
+///   }
+/// }
+/// ```
+#[doc(hidden)]
+#[macro_export]
 macro_rules! const_enum {
-  ($(#[$ty_attrs:meta])* $name:ident($inner:ty) {
+  ($(#[$ty_attrs:meta])* $name:ident($inner:ty $(as $wide:ty)?) {
     $(
       $(#[$const_attrs:meta])*
       $c:ident($v:expr)
@@ -72,18 +117,221 @@ macro_rules! const_enum {
   }) => {
     $(#[$ty_attrs])*
     #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(
+      feature = "serde",
+      derive(serde::Serialize, serde::Deserialize),
+      serde(transparent)
+    )]
     #[repr(transparent)]
     pub struct $name($inner);
     #[allow(non_upper_case_globals)]
     impl $name {
       $( $(#[$const_attrs])* pub const $c: $name = $name($v); )+
+
+      /// All of the declared values, in declaration order.
+      pub const ALL: &'static [$name] = &[$($name::$c),+];
+
+      $(
+        /// The raw stored value, widened for use in a larger register.
+        #[inline]
+        #[must_use]
+        pub const fn widen(self) -> $wide {
+          self.0 as $wide
+        }
+      )?
+
+      /// The raw stored value, already shifted into its field's position.
+      ///
+      /// This isn't the same as [`as_index`](Self::as_index), which is the
+      /// declaration position.
+      #[inline]
+      #[must_use]
+      pub const fn repr_value(self) -> $inner {
+        self.0
+      }
+
+      /// The raw stored value, for writing the value on its own. This is the
+      /// same as [`repr_value`](Self::repr_value).
+      #[inline]
+      #[must_use]
+      pub const fn to_bits(self) -> $inner {
+        self.0
+      }
+
+      /// Wraps a raw value without checking it.
+      ///
+      /// ## Safety
+      ///
+      /// `bits` must be one of the declared values. Use
+      /// [`from_bits_checked`](Self::from_bits_checked) when that isn't
+      /// known.
+      #[inline]
+      #[must_use]
+      pub const unsafe fn from_bits(bits: $inner) -> Self {
+        Self(bits)
+      }
+
+      /// The position of this value within [`ALL`](Self::ALL).
+      ///
+      /// Gives `None` if this isn't one of the declared values.
+      #[inline]
+      #[must_use]
+      pub const fn as_index(self) -> Option<usize> {
+        let mut i = 0;
+        while i < Self::ALL.len() {
+          if Self::ALL[i].0 == self.0 {
+            return Some(i);
+          }
+          i += 1;
+        }
+        None
+      }
+
+      /// The declared value with the given raw value.
+      ///
+      /// Gives `None` if the raw value isn't one of the declared values, such
+      /// as one read from a register with a prohibited setting.
+      #[inline]
+      #[must_use]
+      pub const fn from_bits_checked(bits: $inner) -> Option<Self> {
+        let mut i = 0;
+        while i < Self::ALL.len() {
+          if Self::ALL[i].0 == bits {
+            return Some(Self::ALL[i]);
+          }
+          i += 1;
+        }
+        None
+      }
+
+      /// The value at the given position within [`ALL`](Self::ALL).
+      #[inline]
+      #[must_use]
+      pub const fn from_index(index: usize) -> Option<Self> {
+        if index < Self::ALL.len() {
+          Some(Self::ALL[index])
+        } else {
+          None
+        }
+      }
+
+      /// The next declared value, wrapping around after the last one.
+      ///
+      /// If this isn't one of the declared values you get the first value.
+      #[inline]
+      #[must_use]
+      pub const fn cycle(self) -> Self {
+        match self.as_index() {
+          Some(i) => Self::ALL[(i + 1) % Self::ALL.len()],
+          None => Self::ALL[0],
+        }
+      }
+
+      /// The next declared value, staying put at the last one.
+      ///
+      /// If this isn't one of the declared values you get the first value.
+      #[inline]
+      #[must_use]
+      pub const fn saturating_next(self) -> Self {
+        match self.as_index() {
+          Some(i) if i + 1 < Self::ALL.len() => Self::ALL[i + 1],
+          Some(_) => self,
+          None => Self::ALL[0],
+        }
+      }
+
+      /// The previous declared value, staying put at the first one.
+      ///
+      /// If this isn't one of the declared values you get the first value.
+      #[inline]
+      #[must_use]
+      pub const fn saturating_prev(self) -> Self {
+        match self.as_index() {
+          Some(i) if i > 0 => Self::ALL[i - 1],
+          _ => Self::ALL[0],
+        }
+      }
+
+      /// Every declared value once, starting with this one and wrapping
+      /// around after the last one.
+      ///
+      /// If this isn't one of the declared values you start at the first
+      /// value.
+      #[inline]
+      pub fn iter_from(self) -> impl Iterator<Item = Self> {
+        let start = self.as_index().unwrap_or(0);
+        let len = Self::ALL.len();
+        (0..len).map(move |i| Self::ALL[(start + i) % len])
+      }
+
+      /// If this is equal to any of the `candidates`.
+      #[inline]
+      #[must_use]
+      pub const fn matches_any(self, candidates: &[Self]) -> bool {
+        let mut i = 0;
+        while i < candidates.len() {
+          if candidates[i].0 == self.0 {
+            return true;
+          }
+          i += 1;
+        }
+        false
+      }
     }
+    // Two consts with the same value would make `as_index` and the other
+    // lookups ambiguous, so a copy-paste slip is a compile error.
+    const _: () = {
+      let mut i = 0;
+      while i < $name::ALL.len() {
+        let mut j = i + 1;
+        while j < $name::ALL.len() {
+          assert!(
+            $name::ALL[i].0 != $name::ALL[j].0,
+            concat!("duplicate value in ", stringify!($name))
+          );
+          j += 1;
+        }
+        i += 1;
+      }
+    };
+    impl ::core::convert::TryFrom<$crate::DeclarationIndex> for $name {
+      type Error = $crate::DeclarationIndex;
+      #[inline]
+      fn try_from(
+        index: $crate::DeclarationIndex,
+      ) -> Result<Self, $crate::DeclarationIndex> {
+        Self::from_index(index.0).ok_or(index)
+      }
+    }
+    impl ::core::convert::TryFrom<$inner> for $name {
+      type Error = $inner;
+      #[inline]
+      fn try_from(bits: $inner) -> Result<Self, $inner> {
+        Self::from_bits_checked(bits).ok_or(bits)
+      }
+    }
+    // Safety: the type is a `repr(transparent)` wrapper around a primitive
+    // integer, and any bit pattern is allowed.
+    #[cfg(feature = "bytemuck")]
+    unsafe impl ::bytemuck::Zeroable for $name {}
+    #[cfg(feature = "bytemuck")]
+    unsafe impl ::bytemuck::Pod for $name {}
   }
 }
 
+/// A position within the `ALL` list of a `const_enum!` type.
+///
+/// Every `const_enum!` type implements `TryFrom<DeclarationIndex>`, which
+/// looks up a value by its declaration position, the same as `from_index`.
+/// That's separate from the type's raw value: `BlendEffect::AlphaBlend` is at
+/// index 1 but its raw value is `1 << 6`. The error gives back the index if
+/// it's out of range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DeclarationIndex(pub usize);
+
 macro_rules! phantom_field_get {
   // bools
-  ($(#[$field_attrs:meta])* $inner:ty, $bit:literal : $g:ident, $s:ident) => {
+  ($(#[$field_attrs:meta])* $inner:ty, $bit:literal : $g:ident, $s:ident $(, $t:ident)?) => {
     $(#[$field_attrs])*
     #[inline]
     #[must_use]
@@ -110,24 +358,31 @@ macro_rules! phantom_field_get {
     pub const fn $g(self) -> $nt {
       const MASK: $inner =
         ((((1_u64 << ($end + 1)) - 1) >> $start) << $start) as $inner;
-      $nt(bit_get!(self.0, MASK) as $inner)
+      $nt(bit_get!(self.0, MASK) as _)
     }
   };
 }
 
 macro_rules! phantom_field_set {
   // bools
-  ($inner:ty, $bit:literal : $g:ident, $s:ident) => {
-    ///
+  ($inner:ty, $bit:literal : $g:ident, $s:ident $(, $t:ident)?) => {
+    #[doc = concat!("Sets the `", stringify!($g), "` field.")]
     #[inline]
     pub const fn $s(&mut self, $g: bool) {
       *self =
         Self(bit_set!(self.0, 1 << $bit, ($g as usize) << $bit) as $inner);
     }
+    $(
+      #[doc = concat!("Flips the `", stringify!($g), "` bit.")]
+      #[inline]
+      pub const fn $t(&mut self) {
+        *self = Self((self.0 as usize ^ (1 << $bit)) as $inner);
+      }
+    )?
   };
   // raw ints
   ($inner:ty, $start:literal - $end:literal : $g:ident, $s:ident) => {
-    ///
+    #[doc = concat!("Sets the `", stringify!($g), "` field.")]
     #[inline]
     pub const fn $s(&mut self, $g: $inner) {
       const MASK: $inner =
@@ -137,7 +392,7 @@ macro_rules! phantom_field_set {
   };
   // newtype'd ints
   ($inner:ty, $start:literal - $end:literal => $nt:ident : $g:ident, $s:ident) => {
-    ///
+    #[doc = concat!("Sets the `", stringify!($g), "` field.")]
     #[inline]
     pub const fn $s(&mut self, $g: $nt) {
       const MASK: $inner =
@@ -147,6 +402,20 @@ macro_rules! phantom_field_set {
   };
 }
 
+macro_rules! phantom_field_info {
+  // bools
+  ($bit:literal : $g:ident, $s:ident $(, $t:ident)?) => {
+    $crate::RegisterField { name: stringify!($g), mask: 1 << $bit }
+  };
+  // raw ints and newtype'd ints
+  ($start:literal - $end:literal $(=> $nt:ident)? : $g:ident, $s:ident) => {
+    $crate::RegisterField {
+      name: stringify!($g),
+      mask: ((((1_u64 << ($end + 1)) - 1) >> $start) << $start) as u32,
+    }
+  };
+}
+
 macro_rules! bitstruct_newtype {
   ($(#[$ty_attrs:meta])* $name:ident($inner:ty) {
     $(
@@ -156,15 +425,115 @@ macro_rules! bitstruct_newtype {
   }) => {
     $(#[$ty_attrs])*
     #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(
+      feature = "serde",
+      derive(serde::Serialize, serde::Deserialize),
+      serde(transparent)
+    )]
     #[repr(transparent)]
     pub struct $name($inner);
     impl $name {
       $(phantom_field_get!($(#[$field_attrs])* $inner, $($field_tokens)*);)+
       $(phantom_field_set!(/*no attrs on the setter*/ $inner, $($field_tokens)*);)+
     }
+    impl $crate::Register for $name {
+      type Inner = $inner;
+      const FIELDS: &'static [$crate::RegisterField] =
+        &[$(phantom_field_info!($($field_tokens)*)),+];
+      #[inline]
+      fn from_bits(bits: $inner) -> Self {
+        Self(bits)
+      }
+      #[inline]
+      fn to_bits(self) -> $inner {
+        self.0
+      }
+    }
+    // Safety: the type is a `repr(transparent)` wrapper around a primitive
+    // integer, and any bit pattern is allowed.
+    #[cfg(feature = "bytemuck")]
+    unsafe impl ::bytemuck::Zeroable for $name {}
+    #[cfg(feature = "bytemuck")]
+    unsafe impl ::bytemuck::Pod for $name {}
+  }
+}
+
+/// A value that's stored in an MMIO register.
+///
+/// Every `bitstruct_newtype!` type implements this, which lets you move
+/// between the typed value and the raw integer that the hardware sees.
+pub trait Register: Copy {
+  /// The primitive integer type of the register.
+  type Inner: Copy;
+
+  /// The register's fields, in declaration order.
+  const FIELDS: &'static [RegisterField];
+
+  /// Wraps a raw register value.
+  fn from_bits(bits: Self::Inner) -> Self;
+
+  /// Unwraps the raw register value.
+  fn to_bits(self) -> Self::Inner;
+
+  /// Does a volatile read of the register at `ptr`.
+  ///
+  /// ## Safety
+  ///
+  /// `ptr` must be valid for a volatile read of `Self::Inner`. Normally that
+  /// means it's the aligned MMIO address of a readable register of this type.
+  #[inline]
+  #[must_use]
+  unsafe fn read_volatile_from(ptr: *const Self::Inner) -> Self {
+    Self::from_bits(core::ptr::read_volatile(ptr))
+  }
+
+  /// Does a volatile write of this value to the register at `ptr`.
+  ///
+  /// ## Safety
+  ///
+  /// `ptr` must be valid for a volatile write of `Self::Inner`. Normally that
+  /// means it's the aligned MMIO address of a writable register of this type.
+  #[inline]
+  unsafe fn write_volatile_to(self, ptr: *mut Self::Inner) {
+    core::ptr::write_volatile(ptr, self.to_bits())
   }
 }
 
+/// The name and bits of one field of a [`Register`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RegisterField {
+  /// The name of the field's getter.
+  pub name: &'static str,
+  /// The bits of the register that the field uses.
+  pub mask: u32,
+}
+
+/// The bits that are different between two values of a register.
+#[inline]
+#[must_use]
+pub fn changed_bits<T: Register>(old: T, new: T) -> T::Inner
+where
+  T::Inner: core::ops::BitXor<Output = T::Inner>,
+{
+  old.to_bits() ^ new.to_bits()
+}
+
+/// The fields that are different between two values of a register, in
+/// declaration order.
+///
+/// This is meant for debug tooling such as a register write log.
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn changed_fields<T: Register>(
+  old: T, new: T,
+) -> alloc::vec::Vec<RegisterField>
+where
+  T::Inner: core::ops::BitXor<Output = T::Inner> + Into<u32>,
+{
+  let changed: u32 = changed_bits(old, new).into();
+  T::FIELDS.iter().filter(|f| f.mask & changed != 0).copied().collect()
+}
+
 const_enum! {
   /// One of the six video modes available on the GBA.
   VideoMode(u16) {
@@ -183,6 +552,56 @@ const_enum! {
   }
 }
 
+impl VideoMode {
+  /// The bits per pixel of the mode's bitmap framebuffer.
+  ///
+  /// Modes 3 and 5 are 16bpp direct color, and mode 4 is 8bpp indexed color.
+  /// The tiled modes have no framebuffer, so they give `None`.
+  #[inline]
+  #[must_use]
+  pub const fn framebuffer_bpp(self) -> Option<u8> {
+    match self {
+      VideoMode::_3 | VideoMode::_5 => Some(16),
+      VideoMode::_4 => Some(8),
+      _ => None,
+    }
+  }
+
+  /// If this is one of the bitmap modes (3, 4, or 5).
+  #[inline]
+  #[must_use]
+  pub const fn is_bitmap(self) -> bool {
+    matches!(self, VideoMode::_3 | VideoMode::_4 | VideoMode::_5)
+  }
+
+  /// Which of bg0 through bg3 exist in this mode.
+  #[inline]
+  #[must_use]
+  pub const fn backgrounds(self) -> [bool; 4] {
+    match self {
+      VideoMode::_0 => [true, true, true, true],
+      VideoMode::_1 => [true, true, true, false],
+      VideoMode::_2 => [false, false, true, true],
+      VideoMode::_3 | VideoMode::_4 | VideoMode::_5 => {
+        [false, false, true, false]
+      }
+      _ => [false; 4],
+    }
+  }
+}
+
+const_enum! {
+  /// How object tiles are laid out in object video memory.
+  ObjVramMapping(u16) {
+    /// Object VRAM is a 32x32 grid of tiles. Each row of a multi-tile object
+    /// starts 32 tiles after the previous row.
+    TwoDimensional(0 << 6),
+    /// Object VRAM is a plain array of tiles. Each row of a multi-tile object
+    /// directly follows the previous row.
+    OneDimensional(1 << 6),
+  }
+}
+
 bitstruct_newtype! {
   /// The Display Control register value.
   ///
@@ -194,14 +613,14 @@ bitstruct_newtype! {
 
     /// Determines if Frame 0 or Frame 1 is shown when using video mode 4 or 5.
     /// Otherwise this has no effect.
-    [4: show_frame1, set_show_frame1],
+    [4: show_frame1, set_show_frame1, toggle_show_frame1],
 
     /// This sets the OAM memory to be free for CPU editing during Hblank.
     ///
     /// The downside is that this gives the PPU less time for object display
     /// calculation, so the number of objects that can be displayed per line
     /// goes down slightly (by about 20%).
-    [5: hblank_oam_free, set_hblank_oam_free],
+    [5: hblank_oam_free, set_hblank_oam_free, toggle_hblank_oam_free],
 
     /// Determines if the object tile memory region should be "2d" or "1d" when
     /// displaying multi-tile objects.
@@ -209,39 +628,212 @@ bitstruct_newtype! {
     /// In 2d mode, the object video memory is considered to be a single 256x256
     /// area, so each row is 32 tiles offset. In 1d mode, the object video
     /// memory is considered more like a standard array of tiles.
-    [6: obj_vram_is_1d, set_obj_vram_is_1d],
+    [6: obj_vram_is_1d, set_obj_vram_is_1d, toggle_obj_vram_is_1d],
 
     /// If this bit is active, the ppu is blanked regardless of its actual position.
     ///
     /// While force-blank is on, the ppu only draws white pixels.
-    [7: forced_blank, set_forced_blank],
+    [7: forced_blank, set_forced_blank, toggle_forced_blank],
 
     /// Display background 0. Only effective in video modes 0 and 1.
-    [8: display_bg0, set_display_bg0],
+    [8: display_bg0, set_display_bg0, toggle_display_bg0],
 
     /// Display background 1. Only effective in video modes 0 and 1.
-    [9: display_bg1, set_display_bg1],
+    [9: display_bg1, set_display_bg1, toggle_display_bg1],
 
     /// Display background 2. BG2 has some sort of content in all video modes.
-    [10: display_bg2, set_display_bg2],
+    [10: display_bg2, set_display_bg2, toggle_display_bg2],
 
     /// Display background 3. Only effective in video modes 0 and 2.
-    [11: display_bg3, set_display_bg3],
+    [11: display_bg3, set_display_bg3, toggle_display_bg3],
 
     /// Display the object layer. Available in all video modes.
-    [12: display_obj, set_display_obj],
+    [12: display_obj, set_display_obj, toggle_display_obj],
 
     /// Display window 0 content.
-    [13: display_win0, set_display_win0],
+    [13: display_win0, set_display_win0, toggle_display_win0],
 
     /// Display window 1 content.
-    [14: display_win1, set_display_win1],
+    [14: display_win1, set_display_win1, toggle_display_win1],
 
     /// Display object window content.
-    [15: display_obj_win, set_display_obj_win],
+    [15: display_obj_win, set_display_obj_win, toggle_display_obj_win],
+  }
+}
+
+impl DisplayControlSetting {
+  /// The same bit as `obj_vram_is_1d`, as an [`ObjVramMapping`] value.
+  #[inline]
+  #[must_use]
+  pub const fn obj_vram_mapping(self) -> ObjVramMapping {
+    if self.obj_vram_is_1d() {
+      ObjVramMapping::OneDimensional
+    } else {
+      ObjVramMapping::TwoDimensional
+    }
+  }
+
+  /// Sets the `obj_vram_is_1d` bit from an [`ObjVramMapping`] value.
+  #[inline]
+  pub const fn set_obj_vram_mapping(&mut self, mapping: ObjVramMapping) {
+    self.set_obj_vram_is_1d(mapping.0 != 0);
+  }
+
+  /// The tile index stride between one row of an object and the next, given
+  /// the object's width in tiles.
+  ///
+  /// Tile indexes always count in 4bpp (32 byte) units, so for an 8bpp object
+  /// pass double the width.
+  #[inline]
+  #[must_use]
+  pub const fn tile_stride(self, sprite_width_tiles: u16) -> u16 {
+    match self.obj_vram_mapping() {
+      ObjVramMapping::OneDimensional => sprite_width_tiles,
+      _ => 32,
+    }
+  }
+
+  /// Which of bg0 through bg3 are both enabled and exist in the current
+  /// video mode.
+  #[inline]
+  #[must_use]
+  pub const fn active_backgrounds(self) -> [bool; 4] {
+    let available = self.video_mode().backgrounds();
+    [
+      self.display_bg0() && available[0],
+      self.display_bg1() && available[1],
+      self.display_bg2() && available[2],
+      self.display_bg3() && available[3],
+    ]
+  }
+
+  /// This setting with forced blank turned on or off.
+  ///
+  /// The usual way to safely make big VRAM updates outside of vblank is to
+  /// blank the display, do the update, then unblank:
+  ///
+  /// 1. Write `display.with_forced_blank(true)` to `DISPCNT`.
+  /// 2. Update VRAM, OAM, and palette RAM as needed.
+  /// 3. Write `display.with_forced_blank(false)` to `DISPCNT`.
+  ///
+  /// While blanked the screen shows white, so keep the update short.
+  #[inline]
+  #[must_use]
+  pub const fn with_forced_blank(self, on: bool) -> Self {
+    let mut display = self;
+    display.set_forced_blank(on);
+    display
+  }
+
+  /// A starting point for a sprite based game: mode 0 with the object layer
+  /// shown and 1D object VRAM mapping.
+  ///
+  /// No backgrounds are shown and forced blank is off, so turn on whatever
+  /// backgrounds you need.
+  #[inline]
+  #[must_use]
+  pub const fn sprites_mode0() -> Self {
+    let mut display = Self(0);
+    display.set_video_mode(VideoMode::_0);
+    display.set_obj_vram_mapping(ObjVramMapping::OneDimensional);
+    display.set_display_obj(true);
+    display
+  }
+
+  /// How many backgrounds the video mode has.
+  ///
+  /// This is 4 in mode 0, 3 in mode 1, 2 in mode 2, and 1 in the bitmap
+  /// modes. Use [`VideoMode::backgrounds`] to see which ones they are.
+  ///
+  /// Mode 1 is sometimes listed as having 2 backgrounds, but it has bg0 and
+  /// bg1 as text backgrounds plus bg2 as an affine background, so this gives
+  /// 3 to match the hardware.
+  #[inline]
+  #[must_use]
+  pub const fn available_bg_count(self) -> u8 {
+    let backgrounds = self.video_mode().backgrounds();
+    let mut count = 0;
+    let mut i = 0;
+    while i < backgrounds.len() {
+      count += backgrounds[i] as u8;
+      i += 1;
+    }
+    count
+  }
+
+  /// An estimate of how many objects can be drawn on one scanline.
+  ///
+  /// The PPU has 1210 cycles per line for object rendering, but only 954 if
+  /// `hblank_oam_free` is set. This scales the 128 objects in OAM by that
+  /// ratio, so it gives 128 normally and 100 with the bit set. The real limit
+  /// depends on the size of the objects and if they're affine, so treat this
+  /// as a rough budget.
+  #[inline]
+  #[must_use]
+  pub const fn max_objects_per_line(self) -> u16 {
+    if self.hblank_oam_free() {
+      (128_u32 * 954 / 1210) as u16
+    } else {
+      128
+    }
+  }
+
+  /// Checks for settings that don't do anything, or are likely mistakes.
+  ///
+  /// This only looks at this register, so it can't tell if an enabled window
+  /// has anything set to show inside it. The first problem found is returned.
+  #[inline]
+  pub const fn validate(self) -> Result<(), DisplayConfigError> {
+    let mode = self.video_mode();
+    if mode.as_index().is_none() {
+      return Err(DisplayConfigError::InvalidVideoMode);
+    }
+    let available = mode.backgrounds();
+    let enabled = [
+      self.display_bg0(),
+      self.display_bg1(),
+      self.display_bg2(),
+      self.display_bg3(),
+    ];
+    let mut bg = 0;
+    while bg < 4 {
+      if enabled[bg] && !available[bg] {
+        return Err(DisplayConfigError::BackgroundNotInMode(bg as u8));
+      }
+      bg += 1;
+    }
+    if self.display_obj_win() && !self.display_obj() {
+      return Err(DisplayConfigError::ObjWindowWithoutObjects);
+    }
+    let any_layer = enabled[0]
+      || enabled[1]
+      || enabled[2]
+      || enabled[3]
+      || self.display_obj();
+    if self.forced_blank() && any_layer {
+      return Err(DisplayConfigError::ForcedBlankWithLayers);
+    }
+    Ok(())
   }
 }
 
+/// A problem with a display setting, found by
+/// [`DisplayControlSetting::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DisplayConfigError {
+  /// The video mode is 6 or 7, which are prohibited.
+  InvalidVideoMode,
+  /// A background is enabled that doesn't exist in the video mode. This has
+  /// the background's number.
+  BackgroundNotInMode(u8),
+  /// The object window is enabled but the object layer isn't, so the window
+  /// is always empty.
+  ObjWindowWithoutObjects,
+  /// Forced blank is on while layers are enabled. Nothing will be drawn until
+  /// forced blank is turned off.
+  ForcedBlankWithLayers,
+}
+
 bitstruct_newtype! {
   /// The Display Status register value.
   ///
@@ -249,28 +841,51 @@ bitstruct_newtype! {
   /// well as controlling if/when the display can generates interrupts.
   DisplayStatusSetting(u16) {
     /// Is the PPU currently in vertical blank?
-    [0: is_vblank, set_is_vblank],
+    [0: is_vblank, set_is_vblank, toggle_is_vblank],
 
     /// Is the PPU currently in horizontal blank?
-    [1: is_hblank, set_is_hblank],
+    [1: is_hblank, set_is_hblank, toggle_is_hblank],
 
     /// Is the current vcount a match with the vcount setting?
-    [2: is_vcount_match, set_is_vcount_match],
+    [2: is_vcount_match, set_is_vcount_match, toggle_is_vcount_match],
 
     /// If set, the PPU fires an interrupt when vblank starts.
-    [3: vblank_irq_enabled, set_vblank_irq_enabled],
+    [3: vblank_irq_enabled, set_vblank_irq_enabled, toggle_vblank_irq_enabled],
 
     /// If set, the PPU fires an interrupt when hblank starts.
-    [4: hblank_irq_enabled, set_hblank_irq_enabled],
+    [4: hblank_irq_enabled, set_hblank_irq_enabled, toggle_hblank_irq_enabled],
 
     /// If set, the PPU fires an interrupt when the vcount matches the vcount setting.
-    [5: vcount_match_irq_enabled, set_vcount_match_irq_enabled],
+    [5: vcount_match_irq_enabled, set_vcount_match_irq_enabled, toggle_vcount_match_irq_enabled],
 
     /// This primarily lets you trigger an interrupt at a variable row position.
     [8-15: vcount_setting, set_vcount_setting],
   }
 }
 
+impl DisplayStatusSetting {
+  /// The status bits the PPU reports at a position in the frame, for
+  /// emulators.
+  ///
+  /// `vcount` is the current scanline (0 through 227) and `hdot` is the dot
+  /// within that line (0 through 307). The vblank bit is set on lines 160
+  /// through 226, but not 227, and the hblank bit is set from dot 240 onward.
+  /// The vcount match bit is set when `vcount` equals `vcount_setting`, which
+  /// is also stored in the result. None of the interrupt enable bits are set.
+  #[inline]
+  #[must_use]
+  pub const fn from_ppu_position(
+    vcount: u16, hdot: u16, vcount_setting: u8,
+  ) -> Self {
+    let mut status = Self(0);
+    status.set_is_vblank(vcount >= 160 && vcount <= 226);
+    status.set_is_hblank(hdot >= 240);
+    status.set_is_vcount_match(vcount == vcount_setting as u16);
+    status.set_vcount_setting(vcount_setting as u16);
+    status
+  }
+}
+
 bitstruct_newtype! {
   BackgroundControlSetting(u16) {
     /// Lower priority draws "closer" to the top.
@@ -281,22 +896,373 @@ bitstruct_newtype! {
     /// ```
     [0-1: background_priority, set_background_priority],
     [2-3: base_charblock, set_base_charblock],
-    [6: use_mosaic, set_use_mosaic],
-    [7: is_8bpp, set_is_8bpp],
+    [6: use_mosaic, set_use_mosaic, toggle_use_mosaic],
+    [7: is_8bpp, set_is_8bpp, toggle_is_8bpp],
     [8-12: base_screenblock, set_base_screenblock],
-    [13: affine_overflow_wraparound, set_affine_overflow_wraparound],
+    [13: affine_overflow_wraparound, set_affine_overflow_wraparound, toggle_affine_overflow_wraparound],
     [14-15: screen_size, set_screen_size],
   }
 }
 
+impl BackgroundControlSetting {
+  /// The highest tile index that this background can use.
+  ///
+  /// Screen entries can refer to tiles up to 1023, but tiles are counted from
+  /// the base charblock and there's only 64KiB of background VRAM (4
+  /// charblocks). Starting at a later charblock, or using 8bpp tiles, leaves
+  /// room for fewer tiles than that.
+  #[inline]
+  #[must_use]
+  pub const fn max_tile_id(self) -> u16 {
+    let tile_bytes = if self.is_8bpp() { 64 } else { 32 };
+    let tiles = (4 - self.base_charblock() as u32) * 16 * 1024 / tile_bytes;
+    if tiles > 1024 {
+      1023
+    } else {
+      (tiles - 1) as u16
+    }
+  }
+
+  /// Checks that the background's tiles and screenblocks fit in VRAM without
+  /// overlapping.
+  ///
+  /// The tile region is taken to be the first 512 tiles from the base
+  /// charblock, which is one charblock of 4bpp tiles or two charblocks of 8bpp
+  /// tiles. Backgrounds that use fewer tiles than that could still share the
+  /// space, so this is a conservative check. The screenblock size assumes a
+  /// text background.
+  #[inline]
+  pub const fn validate(self) -> Result<(), BgConfigError> {
+    let screenblocks = match self.screen_size() {
+      0 => 1,
+      1 | 2 => 2,
+      _ => 4,
+    };
+    let screen_start = self.base_screenblock() as u32 * 2 * 1024;
+    let screen_end = screen_start + screenblocks * 2 * 1024;
+    if screen_end > 64 * 1024 {
+      return Err(BgConfigError::ScreenblockOutOfBounds);
+    }
+    let tile_bytes = if self.is_8bpp() { 64 } else { 32 };
+    let tile_start = self.base_charblock() as u32 * 16 * 1024;
+    let tile_end = tile_start + 512 * tile_bytes;
+    if screen_start < tile_end && tile_start < screen_end {
+      return Err(BgConfigError::ScreenblockOverlapsTiles);
+    }
+    Ok(())
+  }
+}
+
+/// A problem with the VRAM layout of a background, found by
+/// [`BackgroundControlSetting::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BgConfigError {
+  /// The screenblocks overlap the background's tiles.
+  ScreenblockOverlapsTiles,
+  /// The screenblocks go past the end of background VRAM.
+  ScreenblockOutOfBounds,
+}
+
+bitstruct_newtype! {
+  /// A Background Offset register value (`BGxHOFS` or `BGxVOFS`).
+  ///
+  /// These only affect text backgrounds, and they're write-only.
+  BackgroundOffsetSetting(u16) {
+    /// The scroll offset in pixels. Only 9 bits are stored, so this wraps
+    /// around at 512.
+    [0-8: offset, set_offset],
+  }
+}
+
+/// The horizontal and vertical offsets of a single text background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BackgroundScroll {
+  /// The horizontal offset, written to `BGxHOFS`.
+  pub h: BackgroundOffsetSetting,
+  /// The vertical offset, written to `BGxVOFS`.
+  pub v: BackgroundOffsetSetting,
+}
+
+impl BackgroundScroll {
+  /// Makes a scroll value from pixel offsets, wrapped to the 9-bit range.
+  #[inline]
+  #[must_use]
+  pub const fn from_pixels(x: u16, y: u16) -> Self {
+    let mut h = BackgroundOffsetSetting(0);
+    h.set_offset(x);
+    let mut v = BackgroundOffsetSetting(0);
+    v.set_offset(y);
+    Self { h, v }
+  }
+
+  /// The scroll of a parallax layer that moves at `factor_num / factor_den`
+  /// of the base scroll speed.
+  ///
+  /// For example a factor of 1/2 gives a distant layer that scrolls half as
+  /// fast as the base layer. The scaled offsets are rounded down and then
+  /// wrapped to the 9-bit range.
+  ///
+  /// ## Panics
+  ///
+  /// If `factor_den` is 0.
+  #[inline]
+  #[must_use]
+  pub const fn parallax(
+    base_x: u16, base_y: u16, factor_num: u16, factor_den: u16,
+  ) -> Self {
+    let num = factor_num as u32;
+    let den = factor_den as u32;
+    Self::from_pixels(
+      (base_x as u32 * num / den) as u16,
+      (base_y as u32 * num / den) as u16,
+    )
+  }
+
+  /// Moves the scroll by the given number of pixels on each axis.
+  ///
+  /// Each axis wraps around within the 9-bit range, same as the hardware.
+  #[inline]
+  pub const fn scroll_by(&mut self, dx: i16, dy: i16) {
+    self.h.set_offset(self.h.offset().wrapping_add(dx as u16));
+    self.v.set_offset(self.v.offset().wrapping_add(dy as u16));
+  }
+}
+
+/// One of the `BGxPA` through `BGxPD` affine parameters: a signed 8.8 fixed
+/// point number.
+///
+/// The upper 8 bits are the whole part and the lower 8 bits are 256ths, so
+/// the raw value `0x0100` is 1.0 and `0xFF80` is -0.5.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct AffineParameter(i16);
+
+impl AffineParameter {
+  /// The value 1.0.
+  pub const ONE: Self = Self(0x100);
+
+  /// Wraps a raw 8.8 fixed point value.
+  #[inline]
+  #[must_use]
+  pub const fn from_bits(bits: i16) -> Self {
+    Self(bits)
+  }
+
+  /// The raw 8.8 fixed point value.
+  #[inline]
+  #[must_use]
+  pub const fn to_bits(self) -> i16 {
+    self.0
+  }
+
+  /// The whole part of the value, rounded toward negative infinity.
+  #[inline]
+  #[must_use]
+  pub const fn whole(self) -> i8 {
+    (self.0 >> 8) as i8
+  }
+
+  /// The fractional part of the value, in 256ths.
+  ///
+  /// This is always positive, so `whole + fraction / 256` is the value.
+  #[inline]
+  #[must_use]
+  pub const fn fraction(self) -> u8 {
+    self.0 as u8
+  }
+
+  /// Makes a value from a float, rounded to the nearest 256th.
+  ///
+  /// Values outside of -128.0 through 127.996 are clamped.
+  #[cfg(feature = "libm")]
+  #[inline]
+  #[must_use]
+  pub fn from_f32(value: f32) -> Self {
+    Self(libm::roundf(value * 256.0) as i16)
+  }
+}
+
+/// The four affine parameters of a background, `BGxPA` through `BGxPD`.
+///
+/// These map screen pixels back to texture pixels: moving one pixel right on
+/// screen moves `(pa, pc)` in the texture, and moving one pixel down moves
+/// `(pb, pd)`. So to draw a background at twice the size, use parameters of
+/// 0.5 and not 2.0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AffineMatrix {
+  /// Texture x change per screen pixel in x.
+  pub pa: AffineParameter,
+  /// Texture x change per screen pixel in y.
+  pub pb: AffineParameter,
+  /// Texture y change per screen pixel in x.
+  pub pc: AffineParameter,
+  /// Texture y change per screen pixel in y.
+  pub pd: AffineParameter,
+}
+
+impl AffineMatrix {
+  /// The matrix that draws the texture unchanged.
+  pub const IDENTITY: Self = Self {
+    pa: AffineParameter::ONE,
+    pb: AffineParameter(0),
+    pc: AffineParameter(0),
+    pd: AffineParameter::ONE,
+  };
+}
+
+/// One of the `BGxX` or `BGxY` affine reference point registers: a signed
+/// 20.8 fixed point number, 28 bits in total.
+///
+/// This is the texture position drawn at the top left of the screen. The
+/// register is 32 bits wide, but the top 4 bits are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct BackgroundReferencePoint(i32);
+
+impl BackgroundReferencePoint {
+  /// Wraps a raw 20.8 fixed point value.
+  ///
+  /// Only the low 28 bits are kept, and bit 27 is sign extended into the top
+  /// 4 bits, the same as the hardware does.
+  #[inline]
+  #[must_use]
+  pub const fn from_bits(bits: i32) -> Self {
+    Self((bits << 4) >> 4)
+  }
+
+  /// The raw 20.8 fixed point value, sign extended to 32 bits.
+  #[inline]
+  #[must_use]
+  pub const fn to_bits(self) -> i32 {
+    self.0
+  }
+
+  /// The whole part of the value, rounded toward negative infinity.
+  ///
+  /// This is in the range of a signed 20-bit number.
+  #[inline]
+  #[must_use]
+  pub const fn whole(self) -> i32 {
+    self.0 >> 8
+  }
+
+  /// The fractional part of the value, in 256ths.
+  ///
+  /// This is always positive, so `whole + fraction / 256` is the value.
+  #[inline]
+  #[must_use]
+  pub const fn fraction(self) -> u8 {
+    self.0 as u8
+  }
+}
+
 bitstruct_newtype! {
+  /// What's shown inside of a window, or outside of all windows.
+  ///
+  /// Each of the `WININ` and `WINOUT` registers holds two of these, one per
+  /// byte.
   WindowContentSetting(u8) {
-    [0: display_bg0, set_display_bg0],
-    [1: display_bg1, set_display_bg1],
-    [2: display_bg2, set_display_bg2],
-    [3: display_bg3, set_display_bg3],
-    [4: display_obj, set_display_obj],
-    [5: display_special_effect, set_display_special_effect],
+    /// Display background 0 in this area.
+    [0: display_bg0, set_display_bg0, toggle_display_bg0],
+    /// Display background 1 in this area.
+    [1: display_bg1, set_display_bg1, toggle_display_bg1],
+    /// Display background 2 in this area.
+    [2: display_bg2, set_display_bg2, toggle_display_bg2],
+    /// Display background 3 in this area.
+    [3: display_bg3, set_display_bg3, toggle_display_bg3],
+    /// Display the object layer in this area.
+    [4: display_obj, set_display_obj, toggle_display_obj],
+    /// Apply color special effects in this area.
+    ///
+    /// The effect itself (alpha blending or brightness changes) is picked with
+    /// [`ColorBlendControlSetting`]. When this is clear, no special effect
+    /// happens in this area even if the blend control has one set.
+    [5: display_special_effect, set_display_special_effect, toggle_display_special_effect],
+  }
+}
+
+impl WindowContentSetting {
+  /// Shows every layer, with special effects enabled.
+  #[inline]
+  #[must_use]
+  pub const fn show_all() -> Self {
+    Self(0b11_1111)
+  }
+
+  /// Shows nothing at all.
+  #[inline]
+  #[must_use]
+  pub const fn show_none() -> Self {
+    Self(0)
+  }
+
+  /// Shows only the object layer, with special effects disabled.
+  #[inline]
+  #[must_use]
+  pub const fn objects_only() -> Self {
+    Self(1 << 4)
+  }
+
+  /// If color special effects (blending) can happen in this area.
+  ///
+  /// This is the same as `display_special_effect`.
+  #[inline]
+  #[must_use]
+  pub const fn allows_blending(self) -> bool {
+    self.display_special_effect()
+  }
+}
+
+bitstruct_newtype! {
+  /// The left and right edges of a window (`WIN0H` or `WIN1H`).
+  ///
+  /// The window covers the columns from `left` up to, but not including,
+  /// `right`. If `right` is more than 240 or less than `left`, the hardware
+  /// treats it as 240, so the window reaches the right edge of the screen.
+  WindowHorizontalSetting(u16) {
+    /// The column just past the right edge of the window.
+    [0-7: right, set_right],
+    /// The leftmost column of the window.
+    [8-15: left, set_left],
+  }
+}
+
+impl WindowHorizontalSetting {
+  /// Makes a setting that covers columns `start` up to, but not including,
+  /// `end`.
+  #[inline]
+  #[must_use]
+  pub const fn new(start: u8, end: u8) -> Self {
+    let mut setting = Self(0);
+    setting.set_left(start as u16);
+    setting.set_right(end as u16);
+    setting
+  }
+}
+
+bitstruct_newtype! {
+  /// The top and bottom edges of a window (`WIN0V` or `WIN1V`).
+  ///
+  /// The window covers the rows from `top` up to, but not including,
+  /// `bottom`. If `bottom` is more than 160 or less than `top`, the hardware
+  /// treats it as 160, so the window reaches the bottom of the screen.
+  WindowVerticalSetting(u16) {
+    /// The row just past the bottom edge of the window.
+    [0-7: bottom, set_bottom],
+    /// The top row of the window.
+    [8-15: top, set_top],
+  }
+}
+
+impl WindowVerticalSetting {
+  /// Makes a setting that covers rows `start` up to, but not including, `end`.
+  #[inline]
+  #[must_use]
+  pub const fn new(start: u8, end: u8) -> Self {
+    let mut setting = Self(0);
+    setting.set_top(start as u16);
+    setting.set_bottom(end as u16);
+    setting
   }
 }
 
@@ -318,107 +1284,1353 @@ const_enum! {
 
 bitstruct_newtype! {
   ColorBlendControlSetting(u16) {
-    [0: first_target_bg0, set_first_target_bg0],
-    [1: first_target_bg1, set_first_target_bg1],
-    [2: first_target_bg2, set_first_target_bg2],
-    [3: first_target_bg3, set_first_target_bg3],
-    [4: first_target_obj, set_first_target_obj],
-    [5: first_target_backdrop, set_first_target_backdrop],
+    [0: first_target_bg0, set_first_target_bg0, toggle_first_target_bg0],
+    [1: first_target_bg1, set_first_target_bg1, toggle_first_target_bg1],
+    [2: first_target_bg2, set_first_target_bg2, toggle_first_target_bg2],
+    [3: first_target_bg3, set_first_target_bg3, toggle_first_target_bg3],
+    [4: first_target_obj, set_first_target_obj, toggle_first_target_obj],
+    [5: first_target_backdrop, set_first_target_backdrop, toggle_first_target_backdrop],
     [6-7 => BlendEffect: blend_effect, set_blend_effect],
-    [8: second_target_bg0, set_second_target_bg0],
-    [9: second_target_bg1, set_second_target_bg1],
-    [10: second_target_bg2, set_second_target_bg2],
-    [11: second_target_bg3, set_second_target_bg3],
-    [12: second_target_obj, set_second_target_obj],
-    [13: second_target_backdrop, set_second_target_backdrop],
+    [8: second_target_bg0, set_second_target_bg0, toggle_second_target_bg0],
+    [9: second_target_bg1, set_second_target_bg1, toggle_second_target_bg1],
+    [10: second_target_bg2, set_second_target_bg2, toggle_second_target_bg2],
+    [11: second_target_bg3, set_second_target_bg3, toggle_second_target_bg3],
+    [12: second_target_obj, set_second_target_obj, toggle_second_target_obj],
+    [13: second_target_backdrop, set_second_target_backdrop, toggle_second_target_backdrop],
   }
 }
 
-bitstruct_newtype! {
-  TextScreenEntry(u16) {
-    [0-9: tile_id, set_tile_id],
-    [10: horizontal_flip, set_horizontal_flip],
-    [11: vertical_flip, set_vertical_flip],
-    [12-15: palbank, set_palbank],
+const_enum! {
+  /// One of the layers that a color special effect can target.
+  BlendLayer(u16) {
+    /// Background 0.
+    Bg0(0),
+    /// Background 1.
+    Bg1(1),
+    /// Background 2.
+    Bg2(2),
+    /// Background 3.
+    Bg3(3),
+    /// The object layer.
+    Obj(4),
+    /// The backdrop, which shows where no other layer is drawn.
+    Backdrop(5),
   }
 }
 
-bitstruct_newtype! {
-  Color(u16) {
-    [0-4: red, set_red],
-    [5-9: green, set_green],
-    [10-14: blue, set_blue],
+impl BlendLayer {
+  /// This layer's bit within the first target bits, or 0 if this isn't a
+  /// declared layer.
+  const fn target_mask(self) -> u16 {
+    if self.0 < 6 {
+      1 << self.0
+    } else {
+      0
+    }
   }
 }
 
-const_enum! {
-  ObjDisplayMode(u16) {
-    Normal(0b00 << 8),
-    Affine(0b01 << 8),
-    Disabled(0b10 << 8),
-    DoubleSizeAffine(0b11 << 8),
+impl ColorBlendControlSetting {
+  /// If the layer is a first target of the blend effect.
+  #[inline]
+  #[must_use]
+  pub const fn is_first_target(self, layer: BlendLayer) -> bool {
+    bit_get!(self.0, layer.target_mask()) != 0
+  }
+
+  /// Sets if the layer is a first target of the blend effect.
+  #[inline]
+  pub const fn set_first_target(&mut self, layer: BlendLayer, target: bool) {
+    let mask = layer.target_mask();
+    let new = if target { mask } else { 0 };
+    *self = Self(bit_set!(self.0, mask, new) as u16);
+  }
+
+  /// If the layer is a second target of the blend effect.
+  #[inline]
+  #[must_use]
+  pub const fn is_second_target(self, layer: BlendLayer) -> bool {
+    bit_get!(self.0, layer.target_mask() << 8) != 0
+  }
+
+  /// Sets if the layer is a second target of the blend effect.
+  #[inline]
+  pub const fn set_second_target(&mut self, layer: BlendLayer, target: bool) {
+    let mask = layer.target_mask() << 8;
+    let new = if target { mask } else { 0 };
+    *self = Self(bit_set!(self.0, mask, new) as u16);
+  }
+
+  /// If a blend effect is selected.
+  ///
+  /// With `NoEffect` the target bits don't do anything.
+  #[inline]
+  #[must_use]
+  pub const fn is_active(self) -> bool {
+    !matches!(self.blend_effect(), BlendEffect::NoEffect)
+  }
+
+  /// Resets to `NoEffect` with no targets selected.
+  #[inline]
+  pub const fn clear(&mut self) {
+    *self = Self(0);
   }
 }
 
 bitstruct_newtype! {
-  ObjAttr0(u16) {
-    [0-7: y_coordinate, set_y_coordinate],
-    [8-9 => ObjDisplayMode: obj_display_mode, set_obj_display_mode],
-    [10-11: obj_mode, set_obj_mode],
-    [12: use_mosaic, set_use_mosaic],
-    [13: is_8bpp, set_is_8bpp],
-    [14-15: obj_shape, set_obj_shape],
+  /// The alpha blend coefficients (`BLDALPHA`), used by
+  /// `BlendEffect::AlphaBlend`.
+  ///
+  /// Each coefficient is in sixteenths. A blended pixel is the first target
+  /// times EVA plus the second target times EVB, capped at full brightness.
+  AlphaBlendCoefficientSetting(u16) {
+    /// The coefficient of the first target. Values above 16 act as 16.
+    [0-4: eva_coefficient, set_eva_coefficient],
+    /// The coefficient of the second target. Values above 16 act as 16.
+    [8-12: evb_coefficient, set_evb_coefficient],
   }
 }
 
-bitstruct_newtype! {
-  ObjAttr1(u16) {
-    [0-8: x_coordinate, set_x_coordinate],
-    [9-13: affine_param, set_affine_param],
-    [12: horizontal_flip, set_horizontal_flip],
-    [13: vertical_flip, set_vertical_flip],
-    [14-15: obj_size, set_obj_size],
+impl AlphaBlendCoefficientSetting {
+  /// The first target coefficient that the hardware uses, 0 through 16.
+  #[inline]
+  #[must_use]
+  pub const fn effective_eva(self) -> u8 {
+    let eva = self.eva_coefficient();
+    if eva > 16 {
+      16
+    } else {
+      eva as u8
+    }
+  }
+
+  /// The second target coefficient that the hardware uses, 0 through 16.
+  #[inline]
+  #[must_use]
+  pub const fn effective_evb(self) -> u8 {
+    let evb = self.evb_coefficient();
+    if evb > 16 {
+      16
+    } else {
+      evb as u8
+    }
   }
 }
 
 bitstruct_newtype! {
-  ObjAttr2(u16) {
-    [0-9: base_tile_id, set_base_tile_id],
-    [10-11: priority, set_priority],
-    [12-15: palbank, set_palbank],
+  /// The brightness coefficient (`BLDY`), used by
+  /// `BlendEffect::BrightnessIncrease` and `BlendEffect::BrightnessDecrease`.
+  ///
+  /// The coefficient is in sixteenths: each first target pixel moves that far
+  /// toward white (for an increase) or black (for a decrease).
+  BrightnessCoefficientSetting(u16) {
+    /// The brightness coefficient. Values above 16 act as 16.
+    [0-4: coefficient, set_coefficient],
   }
 }
 
-pub mod sound;
-
+impl BrightnessCoefficientSetting {
+  /// The coefficient that the hardware uses, 0 through 16.
+  #[inline]
+  #[must_use]
+  pub const fn effective_coefficient(self) -> u8 {
+    let coefficient = self.coefficient();
+    if coefficient > 16 {
+      16
+    } else {
+      coefficient as u8
+    }
+  }
+}
+
+impl ColorBlendControlSetting {
+  /// Works out the color of one pixel with the blend effect applied.
+  ///
+  /// `top` is the topmost layer drawn at the pixel and its color, and `below`
+  /// is the next layer down. Where nothing else is drawn that's the backdrop
+  /// layer, with the color at index 0 of the background palette.
+  ///
+  /// This follows the hardware formulas, but doesn't know about windows that
+  /// disable effects, or semi-transparent objects which always alpha blend.
+  #[inline]
+  #[must_use]
+  pub const fn preview(
+    self, top: (BlendLayer, Color), below: (BlendLayer, Color),
+    alpha: AlphaBlendCoefficientSetting,
+    brightness: BrightnessCoefficientSetting,
+  ) -> Color {
+    let (top_layer, top_color) = top;
+    let (below_layer, below_color) = below;
+    if !self.is_first_target(top_layer) {
+      return top_color;
+    }
+    // The products need more than 8 bits, so do the math in u16.
+    let evy = brightness.effective_coefficient() as u16;
+    let mut channels = top_color.channels();
+    let mut i = 0;
+    match self.blend_effect() {
+      BlendEffect::AlphaBlend => {
+        if self.is_second_target(below_layer) {
+          let (eva, evb) = (alpha.effective_eva(), alpha.effective_evb());
+          return top_color.blend(below_color, eva, evb);
+        }
+      }
+      BlendEffect::BrightnessIncrease => {
+        while i < 3 {
+          let c = channels[i] as u16;
+          channels[i] = (c + (31 - c) * evy / 16) as u8;
+          i += 1;
+        }
+      }
+      BlendEffect::BrightnessDecrease => {
+        while i < 3 {
+          let c = channels[i] as u16;
+          channels[i] = (c - c * evy / 16) as u8;
+          i += 1;
+        }
+      }
+      _ => (),
+    }
+    Color::from_channels(channels)
+  }
+
+  /// Works out the color of a pixel where `top` is drawn directly over the
+  /// backdrop, which is the `backdrop` color.
+  ///
+  /// This is [`preview`](Self::preview) with the backdrop layer below.
+  #[inline]
+  #[must_use]
+  pub const fn preview_over_backdrop(
+    self, top: (BlendLayer, Color), backdrop: Color,
+    alpha: AlphaBlendCoefficientSetting,
+    brightness: BrightnessCoefficientSetting,
+  ) -> Color {
+    self.preview(top, (BlendLayer::Backdrop, backdrop), alpha, brightness)
+  }
+}
+
+bitstruct_newtype! {
+  TextScreenEntry(u16) {
+    [0-9: tile_id, set_tile_id],
+    [10: horizontal_flip, set_horizontal_flip, toggle_horizontal_flip],
+    [11: vertical_flip, set_vertical_flip, toggle_vertical_flip],
+    [12-15: palbank, set_palbank],
+  }
+}
+
+/// The fields of a [`TextScreenEntry`] as a plain struct, for editing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScreenEntryDescriptor {
+  /// The tile to draw, 0 through 1023.
+  pub tile_id: u16,
+  /// If the tile is flipped horizontally.
+  pub h_flip: bool,
+  /// If the tile is flipped vertically.
+  pub v_flip: bool,
+  /// The palbank of a 4bpp tile, 0 through 15.
+  pub palbank: u8,
+}
+
+impl From<TextScreenEntry> for ScreenEntryDescriptor {
+  #[inline]
+  fn from(entry: TextScreenEntry) -> Self {
+    Self {
+      tile_id: entry.tile_id(),
+      h_flip: entry.horizontal_flip(),
+      v_flip: entry.vertical_flip(),
+      palbank: entry.palbank() as u8,
+    }
+  }
+}
+
+/// Values that are out of range only keep their lowest bits.
+impl From<ScreenEntryDescriptor> for TextScreenEntry {
+  #[inline]
+  fn from(descriptor: ScreenEntryDescriptor) -> Self {
+    let mut entry = Self(0);
+    entry.set_tile_id(descriptor.tile_id & 0x3FF);
+    entry.set_horizontal_flip(descriptor.h_flip);
+    entry.set_vertical_flip(descriptor.v_flip);
+    entry.set_palbank(u16::from(descriptor.palbank & 0xF));
+    entry
+  }
+}
+
+bitstruct_newtype! {
+  Color(u16) {
+    [0-4: red, set_red],
+    [5-9: green, set_green],
+    [10-14: blue, set_blue],
+  }
+}
+
+impl Color {
+  /// Black, all channels 0.
+  pub const BLACK: Self = Self(0);
+
+  /// White, all channels 31.
+  pub const WHITE: Self = Self(0x7FFF);
+
+  /// Full red.
+  pub const RED: Self = Self(0x001F);
+
+  /// Full green.
+  pub const GREEN: Self = Self(0x03E0);
+
+  /// Full blue.
+  pub const BLUE: Self = Self(0x7C00);
+
+  /// The color's channels as `[red, green, blue]`, each 0 through 31.
+  #[inline]
+  #[must_use]
+  pub const fn channels(self) -> [u8; 3] {
+    [self.red() as u8, self.green() as u8, self.blue() as u8]
+  }
+
+  /// Makes a color from `[red, green, blue]` channels.
+  ///
+  /// Each channel only keeps its lowest 5 bits.
+  #[inline]
+  #[must_use]
+  pub const fn from_channels(channels: [u8; 3]) -> Self {
+    let mut color = Self(0);
+    color.set_red(channels[0] as u16 & 0b1_1111);
+    color.set_green(channels[1] as u16 & 0b1_1111);
+    color.set_blue(channels[2] as u16 & 0b1_1111);
+    color
+  }
+
+  /// Makes a color from red, green, and blue channels.
+  ///
+  /// Each channel only keeps its lowest 5 bits.
+  #[inline]
+  #[must_use]
+  pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+    Self::from_channels([red, green, blue])
+  }
+
+  /// Makes a color from red, green, and blue channels.
+  ///
+  /// Each channel above 31 is clamped to 31.
+  #[inline]
+  #[must_use]
+  pub const fn from_rgb_clamped(red: u8, green: u8, blue: u8) -> Self {
+    let mut channels = [red, green, blue];
+    let mut i = 0;
+    while i < 3 {
+      if channels[i] > 31 {
+        channels[i] = 31;
+      }
+      i += 1;
+    }
+    Self::from_channels(channels)
+  }
+
+  /// Moves each channel along to the next one, `steps` times.
+  ///
+  /// One step moves red into green, green into blue, and blue into red, which
+  /// is a coarse hue shift. Three steps gets you back to the original color.
+  #[inline]
+  #[must_use]
+  pub const fn rotate_channels(self, steps: u8) -> Self {
+    let [r, g, b] = self.channels();
+    match steps % 3 {
+      0 => self,
+      1 => Self::from_channels([b, r, g]),
+      _ => Self::from_channels([g, b, r]),
+    }
+  }
+
+  /// This color with only its red channel kept.
+  #[inline]
+  #[must_use]
+  pub const fn red_only(self) -> Self {
+    Self(bit_get!(self.0, 0b1_1111) as u16)
+  }
+
+  /// This color with only its green channel kept.
+  #[inline]
+  #[must_use]
+  pub const fn green_only(self) -> Self {
+    Self(bit_get!(self.0, 0b1_1111 << 5) as u16)
+  }
+
+  /// This color with only its blue channel kept.
+  #[inline]
+  #[must_use]
+  pub const fn blue_only(self) -> Self {
+    Self(bit_get!(self.0, 0b1_1111 << 10) as u16)
+  }
+
+  /// Each channel multiplied by a blend coefficient, as `[red, green, blue]`.
+  ///
+  /// Blend coefficients are sixteenths, 0 through 16, and larger values act
+  /// as 16 the same as on hardware. Divide a product (or a sum of products)
+  /// by 16 to get back to a channel value.
+  #[inline]
+  #[must_use]
+  pub const fn premultiply(self, coefficient: u8) -> [u16; 3] {
+    let c = if coefficient > 16 { 16 } else { coefficient as u16 };
+    [self.red() * c, self.green() * c, self.blue() * c]
+  }
+
+  /// The color's brightness, 0 through 31.
+  ///
+  /// This weights the channels by how bright they look, using the usual
+  /// `0.3 red + 0.59 green + 0.11 blue` approximation.
+  #[inline]
+  #[must_use]
+  pub const fn luminance(self) -> u8 {
+    ((77 * self.red() + 150 * self.green() + 29 * self.blue()) >> 8) as u8
+  }
+
+  /// Alpha blends this color with `other`, the same way the hardware blends
+  /// a first target over a second target.
+  ///
+  /// `eva` is this color's coefficient and `evb` is `other`'s, both in
+  /// sixteenths (see [`premultiply`](Self::premultiply)). Each channel is
+  /// `(self * eva + other * evb) / 16`, capped at 31.
+  #[inline]
+  #[must_use]
+  pub const fn blend(self, other: Color, eva: u8, evb: u8) -> Self {
+    let a = self.premultiply(eva);
+    let b = other.premultiply(evb);
+    let mut channels = [0; 3];
+    let mut i = 0;
+    while i < 3 {
+      let c = (a[i] + b[i]) / 16;
+      channels[i] = if c > 31 { 31 } else { c as u8 };
+      i += 1;
+    }
+    Self::from_channels(channels)
+  }
+
+  /// The color `num / den` of the way from this color to `other`, with each
+  /// channel rounded to the nearest value.
+  ///
+  /// A `num` of 0 gives this color, and `num` of `den` or more gives `other`.
+  ///
+  /// ## Panics
+  ///
+  /// If `den` is 0.
+  #[inline]
+  #[must_use]
+  pub const fn lerp(self, other: Color, num: u16, den: u16) -> Self {
+    let num = if num > den { den } else { num } as u32;
+    let den = den as u32;
+    let a = self.channels();
+    let b = other.channels();
+    let mut channels = [0; 3];
+    let mut i = 0;
+    while i < 3 {
+      let v = a[i] as u32 * (den - num) + b[i] as u32 * num;
+      channels[i] = ((v + den / 2) / den) as u8;
+      i += 1;
+    }
+    Self::from_channels(channels)
+  }
+
+  /// Scales how far each channel is from the color's gray
+  /// [`luminance`](Self::luminance) by `factor_num / factor_den`.
+  ///
+  /// A factor of 0 gives a fully gray color, 1 leaves the color unchanged,
+  /// and more than 1 makes it more saturated. Channels are clamped to 0
+  /// through 31.
+  ///
+  /// ## Panics
+  ///
+  /// If `factor_den` is 0.
+  #[inline]
+  #[must_use]
+  pub const fn adjust_saturation(self, factor_num: u8, factor_den: u8) -> Self {
+    let l = self.luminance() as i32;
+    let mut channels = self.channels();
+    let mut i = 0;
+    while i < 3 {
+      let c =
+        l + (channels[i] as i32 - l) * factor_num as i32 / factor_den as i32;
+      channels[i] = if c < 0 {
+        0
+      } else if c > 31 {
+        31
+      } else {
+        c as u8
+      };
+      i += 1;
+    }
+    Self::from_channels(channels)
+  }
+
+  /// Scales how far each channel is from mid-gray (16) by
+  /// `factor_num / factor_den`.
+  ///
+  /// A factor of 0 gives mid-gray, 1 leaves the color unchanged, and more
+  /// than 1 increases the contrast. Channels are clamped to 0 through 31.
+  ///
+  /// ## Panics
+  ///
+  /// If `factor_den` is 0.
+  #[inline]
+  #[must_use]
+  pub const fn adjust_contrast(self, factor_num: u8, factor_den: u8) -> Self {
+    let mut channels = self.channels();
+    let mut i = 0;
+    while i < 3 {
+      let c =
+        16 + (channels[i] as i32 - 16) * factor_num as i32 / factor_den as i32;
+      channels[i] = if c < 0 {
+        0
+      } else if c > 31 {
+        31
+      } else {
+        c as u8
+      };
+      i += 1;
+    }
+    Self::from_channels(channels)
+  }
+
+  /// Tints the color toward `tint_color` by `strength`, 0 through 16.
+  ///
+  /// The fully tinted color is `tint_color` scaled by this color's
+  /// [`luminance`](Self::luminance), so a sepia-brown tint gives sepia.
+  /// `strength` blends from the gray version of this color at 0 to the fully
+  /// tinted color at 16 and above.
+  #[inline]
+  #[must_use]
+  pub const fn tint(self, tint_color: Color, strength: u8) -> Self {
+    let l = self.luminance();
+    let gray = Self::from_rgb(l, l, l);
+    let mut tinted = tint_color.channels();
+    let mut i = 0;
+    while i < 3 {
+      tinted[i] = (tinted[i] as u16 * l as u16 / 31) as u8;
+      i += 1;
+    }
+    let tinted = Self::from_channels(tinted);
+    let s = if strength > 16 { 16 } else { strength };
+    gray.blend(tinted, 16 - s, s)
+  }
+
+  /// If each channel of the two colors is within `tolerance` of the other.
+  #[inline]
+  #[must_use]
+  pub const fn approx_eq(self, other: Color, tolerance: u8) -> bool {
+    let a = self.channels();
+    let b = other.channels();
+    let tolerance = tolerance as u16;
+    let mut i = 0;
+    while i < 3 {
+      let (a, b) = (a[i] as u16, b[i] as u16);
+      if a + tolerance < b || b + tolerance < a {
+        return false;
+      }
+      i += 1;
+    }
+    true
+  }
+
+  /// The color's channels expanded to 8 bits each, as `[red, green, blue]`.
+  ///
+  /// Each channel's top bits are repeated into the low bits, so 0 becomes 0
+  /// and 31 becomes 255.
+  #[inline]
+  #[must_use]
+  pub const fn to_rgb8(self) -> [u8; 3] {
+    let mut rgb = self.channels();
+    let mut i = 0;
+    while i < 3 {
+      rgb[i] = rgb[i] << 3 | rgb[i] >> 2;
+      i += 1;
+    }
+    rgb
+  }
+
+  /// Makes a color from 8-bit `[red, green, blue]` channels, each rounded to
+  /// the nearest 5-bit value.
+  ///
+  /// Going from a color to 8 bits and back gives the same color, but going
+  /// from 8 bits to a color and back only gives one of 32 levels per channel.
+  #[inline]
+  #[must_use]
+  pub const fn from_rgb8(rgb: [u8; 3]) -> Self {
+    let mut channels = [0; 3];
+    let mut i = 0;
+    while i < 3 {
+      channels[i] = ((rgb[i] as u16 * 31 + 127) / 255) as u8;
+      i += 1;
+    }
+    Self::from_channels(channels)
+  }
+
+  /// The color's raw value as little-endian bytes, the order it's stored in
+  /// palette RAM.
+  #[inline]
+  #[must_use]
+  pub const fn to_le_bytes(self) -> [u8; 2] {
+    self.0.to_le_bytes()
+  }
+
+  /// Packs two colors into one `u32`, for 32-bit copies.
+  ///
+  /// `a` goes in the low half, so it's the first color in memory.
+  #[inline]
+  #[must_use]
+  pub const fn pack_pair(a: Color, b: Color) -> u32 {
+    a.0 as u32 | (b.0 as u32) << 16
+  }
+
+  /// Unpacks two colors from one `u32`, low half first.
+  #[inline]
+  #[must_use]
+  pub const fn unpack_pair(pair: u32) -> (Color, Color) {
+    (Color(pair as u16), Color((pair >> 16) as u16))
+  }
+}
+
+/// Fills `out` with a dithered gradient from `from` to `to`.
+///
+/// The first entry of `out` is `from` and the last is `to`. Each entry in
+/// between has channels partway between the two, which usually falls between
+/// two 5-bit values. The entry rounds up when its fractional part is more than
+/// a threshold, and `x_phase` gives the thresholds: entry `i` uses
+/// `x_phase[i % x_phase.len()]`, out of 256. Repeating a small spread of
+/// thresholds such as `[32, 160, 96, 224]` gives an ordered dither, and giving
+/// each row a different phase of the pattern breaks up the banding. An empty
+/// `x_phase` uses 128, which just rounds to the nearest value.
+///
+/// The output never goes outside the range of the two colors.
+pub fn dither_gradient(
+  from: Color, to: Color, out: &mut [Color], x_phase: &[u8],
+) {
+  let steps = match out.len() {
+    0 => return,
+    1 => {
+      out[0] = from;
+      return;
+    }
+    len => len as u32 - 1,
+  };
+  let from = from.channels();
+  let to = to.channels();
+  for (i, o) in out.iter_mut().enumerate() {
+    let i = i as u32;
+    let threshold = match x_phase.len() {
+      0 => 128,
+      len => u32::from(x_phase[i as usize % len]),
+    };
+    let mut channels = [0; 3];
+    for c in 0..3 {
+      let v = u32::from(from[c]) * (steps - i) + u32::from(to[c]) * i;
+      let (whole, part) = (v / steps, v % steps);
+      let round_up = part * 256 > threshold * steps;
+      channels[c] = (whole + round_up as u32) as u8;
+    }
+    *o = Color::from_channels(channels);
+  }
+}
+
+/// Declares a [`Palette16`] or [`Palette256`] from a list of
+/// `(red, green, blue)` channel values.
+///
+/// Name the palette type first, then list the colors. Each color is made with
+/// [`Color::from_rgb`] and the palette with `from_colors`, so this works in a
+/// `const` or `static`. Any colors that aren't listed are black. Listing more
+/// colors than the palette holds is a compile error.
+///
+/// ```
+/// use gba_types::{palette, Color, Palette16, Palette256};
+/// const SUNSET: Palette16 =
+///   palette![Palette16; (0, 0, 0), (31, 12, 0), (31, 24, 8)];
+/// assert_eq!(SUNSET.0[1], Color::from_rgb(31, 12, 0));
+/// assert_eq!(SUNSET.0[3], Color::BLACK);
+///
+/// static SKY: Palette256 = palette![Palette256; (0, 0, 0), (8, 16, 31)];
+/// assert_eq!(SKY.0[1], Color::from_rgb(8, 16, 31));
+/// ```
+///
+/// ```compile_fail
+/// use gba_types::{palette, Palette256};
+/// // 257 colors, one more than a palette can hold.
+/// const TOO_MANY: Palette256 = palette![Palette256;
+///   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// #   (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0), (0, 0, 0),
+/// ];
+/// ```
+#[macro_export]
+macro_rules! palette {
+  (@unit $r:expr) => {
+    ()
+  };
+  (@make $palette:ident, $max:expr, $(($r:expr, $g:expr, $b:expr)),*) => {{
+    const _: () = assert!(
+      <[()]>::len(&[$($crate::palette!(@unit $r)),*]) <= $max,
+      concat!("too many colors for a ", stringify!($palette))
+    );
+    $crate::$palette::from_colors(&[$($crate::Color::from_rgb($r, $g, $b)),*])
+  }};
+  (Palette16; $(($r:expr, $g:expr, $b:expr)),* $(,)?) => {
+    $crate::palette!(@make Palette16, 16, $(($r, $g, $b)),*)
+  };
+  (Palette256; $(($r:expr, $g:expr, $b:expr)),* $(,)?) => {
+    $crate::palette!(@make Palette256, 256, $(($r, $g, $b)),*)
+  };
+}
+
+/// Rotates and xors together all the colors.
+///
+/// The rotation depends only on the position, so each step is reversible and
+/// a change to any one color shows up in the result.
+const fn palette_checksum(colors: &[Color]) -> u32 {
+  let mut sum = 0_u32;
+  let mut i = 0;
+  while i < colors.len() {
+    sum = sum.rotate_left(5) ^ colors[i].0 as u32;
+    i += 1;
+  }
+  sum
+}
+
+/// Writes a gradient over `colors[start..=end]` with [`Color::lerp`].
+fn fill_gradient(
+  colors: &mut [Color], start: u8, end: u8, from: Color, to: Color,
+) {
+  assert!(start <= end, "gradient end is before its start");
+  let steps = u16::from(end) - u16::from(start);
+  let range = &mut colors[usize::from(start)..=usize::from(end)];
+  for (i, color) in (0..).zip(range.iter_mut()) {
+    *color = if steps == 0 { from } else { from.lerp(to, i, steps) };
+  }
+}
+
+/// A single palbank of 16 colors, as used by 4bpp tiles.
+///
+/// Index 0 is transparent: pixels using it are never drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Palette16(pub [Color; 16]);
+
+impl Palette16 {
+  /// The color at index 0.
+  ///
+  /// Pixels using index 0 are transparent and don't render, so this color is
+  /// never shown for them.
+  #[inline]
+  #[must_use]
+  pub const fn transparent(&self) -> Color {
+    self.0[0]
+  }
+
+  /// Sets the color at index 0.
+  #[inline]
+  pub const fn set_transparent(&mut self, color: Color) {
+    self.0[0] = color;
+  }
+
+  /// Makes a palette from raw color values, such as palette RAM read back as
+  /// `u16`.
+  ///
+  /// Extra values past 16 are ignored, and if there are fewer than 16 then
+  /// the rest of the palette is black.
+  #[inline]
+  #[must_use]
+  pub const fn from_u16_slice(raw: &[u16]) -> Self {
+    let mut colors = [Color(0); 16];
+    let mut i = 0;
+    while i < raw.len() && i < colors.len() {
+      colors[i] = Color(raw[i]);
+      i += 1;
+    }
+    Self(colors)
+  }
+
+  /// Makes a palette from a list of colors.
+  ///
+  /// If there are fewer than 16 colors then the rest of the palette is black.
+  ///
+  /// ## Panics
+  ///
+  /// If there are more than 16 colors. In a `const` or `static` this is a
+  /// compile error.
+  #[inline]
+  #[must_use]
+  pub const fn from_colors(list: &[Color]) -> Self {
+    assert!(list.len() <= 16, "too many colors for a Palette16");
+    let mut colors = [Color(0); 16];
+    let mut i = 0;
+    while i < list.len() {
+      colors[i] = list[i];
+      i += 1;
+    }
+    Self(colors)
+  }
+
+  /// A cheap checksum of the palette's colors, for change detection.
+  ///
+  /// Keep the checksum of what was last copied to palette RAM, and you can
+  /// skip the copy when it hasn't changed. Changing any single color always
+  /// changes the checksum, but this isn't a secure hash.
+  #[inline]
+  #[must_use]
+  pub const fn checksum(&self) -> u32 {
+    palette_checksum(&self.0)
+  }
+
+  /// Views the palette as raw color values.
+  #[inline]
+  #[must_use]
+  pub fn as_u16_slice(&self) -> &[u16] {
+    // Safety: `Color` is a `repr(transparent)` wrapper around a `u16`.
+    unsafe {
+      core::slice::from_raw_parts(self.0.as_ptr().cast::<u16>(), self.0.len())
+    }
+  }
+
+  /// Fills the entries from `start` through `end`, inclusive, with a gradient
+  /// from `from` to `to`.
+  ///
+  /// The entry at `start` is `from` and the entry at `end` is `to`. If
+  /// `start` and `end` are the same, that entry is `from`.
+  ///
+  /// ## Panics
+  ///
+  /// If `end` is before `start`, or past index 15.
+  #[inline]
+  pub fn fill_gradient(&mut self, start: u8, end: u8, from: Color, to: Color) {
+    fill_gradient(&mut self.0, start, end, from, to);
+  }
+}
+
+/// A full palette of 256 colors, as used by 8bpp tiles and mode 4.
+///
+/// This is the layout of both the background and object palette RAM. Index 0
+/// is transparent: pixels using it are never drawn. In the background palette
+/// RAM, index 0 is also the backdrop color shown where no layer is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Palette256(pub [Color; 256]);
+
+impl Default for Palette256 {
+  #[inline]
+  fn default() -> Self {
+    Self([Color(0); 256])
+  }
+}
+
+impl Palette256 {
+  /// The color at index 0.
+  ///
+  /// Pixels using index 0 are transparent and don't render, so this color is
+  /// never shown for them.
+  #[inline]
+  #[must_use]
+  pub const fn transparent(&self) -> Color {
+    self.0[0]
+  }
+
+  /// Sets the color at index 0.
+  #[inline]
+  pub const fn set_transparent(&mut self, color: Color) {
+    self.0[0] = color;
+  }
+
+  /// Makes a palette from raw color values, such as palette RAM read back as
+  /// `u16`.
+  ///
+  /// Extra values past 256 are ignored, and if there are fewer than 256 then
+  /// the rest of the palette is black.
+  #[inline]
+  #[must_use]
+  pub const fn from_u16_slice(raw: &[u16]) -> Self {
+    let mut colors = [Color(0); 256];
+    let mut i = 0;
+    while i < raw.len() && i < colors.len() {
+      colors[i] = Color(raw[i]);
+      i += 1;
+    }
+    Self(colors)
+  }
+
+  /// Makes a palette from a list of colors.
+  ///
+  /// If there are fewer than 256 colors then the rest of the palette is black.
+  ///
+  /// ## Panics
+  ///
+  /// If there are more than 256 colors. In a `const` or `static` this is a
+  /// compile error.
+  #[inline]
+  #[must_use]
+  pub const fn from_colors(list: &[Color]) -> Self {
+    assert!(list.len() <= 256, "too many colors for a Palette256");
+    let mut colors = [Color(0); 256];
+    let mut i = 0;
+    while i < list.len() {
+      colors[i] = list[i];
+      i += 1;
+    }
+    Self(colors)
+  }
+
+  /// A cheap checksum of the palette's colors, for change detection.
+  ///
+  /// Keep the checksum of what was last copied to palette RAM, and you can
+  /// skip the copy when it hasn't changed. Changing any single color always
+  /// changes the checksum, but this isn't a secure hash.
+  #[inline]
+  #[must_use]
+  pub const fn checksum(&self) -> u32 {
+    palette_checksum(&self.0)
+  }
+
+  /// Views the palette as raw color values.
+  #[inline]
+  #[must_use]
+  pub fn as_u16_slice(&self) -> &[u16] {
+    // Safety: `Color` is a `repr(transparent)` wrapper around a `u16`.
+    unsafe {
+      core::slice::from_raw_parts(self.0.as_ptr().cast::<u16>(), self.0.len())
+    }
+  }
+
+  /// Fills the entries from `start` through `end`, inclusive, with a gradient
+  /// from `from` to `to`.
+  ///
+  /// The entry at `start` is `from` and the entry at `end` is `to`. If
+  /// `start` and `end` are the same, that entry is `from`.
+  ///
+  /// ## Panics
+  ///
+  /// If `end` is before `start`.
+  #[inline]
+  pub fn fill_gradient(&mut self, start: u8, end: u8, from: Color, to: Color) {
+    fill_gradient(&mut self.0, start, end, from, to);
+  }
+
+  /// All 512 bytes of the palette, in the order they're stored in palette RAM.
+  #[inline]
+  pub fn le_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+    self.0.iter().flat_map(|color| {
+      let [low, high] = color.to_le_bytes();
+      core::iter::once(low).chain(core::iter::once(high))
+    })
+  }
+
+  /// Looks up each index byte in the palette and writes the color to `out`.
+  ///
+  /// If the slices are different lengths, only the shorter length is
+  /// processed.
+  #[inline]
+  pub fn expand_into(&self, indices: &[u8], out: &mut [Color]) {
+    for (i, o) in indices.iter().zip(out.iter_mut()) {
+      *o = self.0[usize::from(*i)];
+    }
+  }
+}
+
+/// A full mode 3 framebuffer: 240x160 direct colors, one row after another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Mode3Bitmap(pub [Color; 240 * 160]);
+
+impl Default for Mode3Bitmap {
+  #[inline]
+  fn default() -> Self {
+    Self([Color(0); 240 * 160])
+  }
+}
+
+impl Mode3Bitmap {
+  /// The width of the bitmap in pixels.
+  pub const WIDTH: usize = 240;
+  /// The height of the bitmap in pixels.
+  pub const HEIGHT: usize = 160;
+
+  /// The pixels of row `y`, or `None` if `y` is 160 or more.
+  #[inline]
+  #[must_use]
+  pub fn row_mut(&mut self, y: usize) -> Option<&mut [Color]> {
+    if y < Self::HEIGHT {
+      Some(&mut self.0[y * Self::WIDTH..(y + 1) * Self::WIDTH])
+    } else {
+      None
+    }
+  }
+}
+
+/// One page of a mode 4 framebuffer: 240x160 palette indexes, one row after
+/// another.
+///
+/// Mode 4 has two of these pages, and `show_frame1` picks which is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Mode4Bitmap(pub [u8; 240 * 160]);
+
+impl Default for Mode4Bitmap {
+  #[inline]
+  fn default() -> Self {
+    Self([0; 240 * 160])
+  }
+}
+
+impl Mode4Bitmap {
+  /// The width of the bitmap in pixels.
+  pub const WIDTH: usize = 240;
+  /// The height of the bitmap in pixels.
+  pub const HEIGHT: usize = 160;
+
+  /// The palette indexes of row `y`, or `None` if `y` is 160 or more.
+  ///
+  /// VRAM can't be written one byte at a time, so when this is a view of VRAM
+  /// the row should be written in pairs of pixels.
+  #[inline]
+  #[must_use]
+  pub fn row_mut(&mut self, y: usize) -> Option<&mut [u8]> {
+    if y < Self::HEIGHT {
+      Some(&mut self.0[y * Self::WIDTH..(y + 1) * Self::WIDTH])
+    } else {
+      None
+    }
+  }
+}
+
+const_enum! {
+  ObjDisplayMode(u16) {
+    Normal(0b00 << 8),
+    Affine(0b01 << 8),
+    Disabled(0b10 << 8),
+    DoubleSizeAffine(0b11 << 8),
+  }
+}
+
+bitstruct_newtype! {
+  ObjAttr0(u16) {
+    [0-7: y_coordinate, set_y_coordinate],
+    [8-9 => ObjDisplayMode: obj_display_mode, set_obj_display_mode],
+    [10-11: obj_mode, set_obj_mode],
+    [12: use_mosaic, set_use_mosaic, toggle_use_mosaic],
+    [13: is_8bpp, set_is_8bpp, toggle_is_8bpp],
+    [14-15: obj_shape, set_obj_shape],
+  }
+}
+
+impl ObjAttr0 {
+  /// The first and last scanline the object covers, for an object with a
+  /// sprite `height` pixels tall.
+  ///
+  /// A double size affine object covers twice its sprite's height. The range
+  /// is inclusive, and since the y coordinate is 8 bits it wraps around after
+  /// line 255, so the last line can be less than the first. Lines 160 and up
+  /// aren't visible, so an object that wraps shows at the top of the screen.
+  #[inline]
+  #[must_use]
+  pub const fn effective_y_range(self, height: u8) -> (u8, u8) {
+    let height = match self.obj_display_mode() {
+      ObjDisplayMode::DoubleSizeAffine => height as u16 * 2,
+      _ => height as u16,
+    };
+    let y = self.y_coordinate();
+    (y as u8, (y + height).wrapping_sub(1) as u8)
+  }
+}
+
+bitstruct_newtype! {
+  ObjAttr1(u16) {
+    [0-8: x_coordinate, set_x_coordinate],
+    [9-13: affine_param, set_affine_param],
+    [12: horizontal_flip, set_horizontal_flip, toggle_horizontal_flip],
+    [13: vertical_flip, set_vertical_flip, toggle_vertical_flip],
+    [14-15: obj_size, set_obj_size],
+  }
+}
+
+bitstruct_newtype! {
+  ObjAttr2(u16) {
+    [0-9: base_tile_id, set_base_tile_id],
+    [10-11: priority, set_priority],
+    [12-15: palbank, set_palbank],
+  }
+}
+
+impl ObjAttr2 {
+  /// Makes the attributes for a 4bpp object in one step.
+  ///
+  /// Gives `None` if any value is out of range: the tile must be 0 through
+  /// 1023, the palbank 0 through 15, and the priority 0 through 3.
+  #[inline]
+  #[must_use]
+  pub const fn configure_4bpp(
+    base_tile: u16, palbank: u8, priority: u8,
+  ) -> Option<Self> {
+    if base_tile > 1023 || palbank > 15 || priority > 3 {
+      return None;
+    }
+    let mut attr = Self(0);
+    attr.set_base_tile_id(base_tile);
+    attr.set_palbank(palbank as u16);
+    attr.set_priority(priority as u16);
+    Some(attr)
+  }
+}
+
+/// The width and height in pixels of an object with the given shape and size.
+///
+/// These are the raw `obj_shape` and `obj_size` field values. Gives `None`
+/// for shape 3, which is prohibited.
+#[inline]
+#[must_use]
+pub const fn obj_dimensions(shape: u16, size: u16) -> Option<(u8, u8)> {
+  Some(match (shape, size & 0b11) {
+    (0, 0) => (8, 8),
+    (0, 1) => (16, 16),
+    (0, 2) => (32, 32),
+    (0, _) => (64, 64),
+    (1, 0) => (16, 8),
+    (1, 1) => (32, 8),
+    (1, 2) => (32, 16),
+    (1, _) => (64, 32),
+    (2, 0) => (8, 16),
+    (2, 1) => (8, 32),
+    (2, 2) => (16, 32),
+    (2, _) => (32, 64),
+    _ => return None,
+  })
+}
+
+/// The three attributes of a single object in OAM.
+///
+/// In OAM each object takes 8 bytes: the three attributes, then 2 bytes that
+/// are part of the affine parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectAttributes {
+  /// Attribute 0: y position, display mode, shape, and so on.
+  pub attr0: ObjAttr0,
+  /// Attribute 1: x position, flips or affine index, and size.
+  pub attr1: ObjAttr1,
+  /// Attribute 2: tile, priority, and palbank.
+  pub attr2: ObjAttr2,
+}
+
+impl ObjectAttributes {
+  /// The width and height of the object's sprite, in pixels.
+  ///
+  /// Gives `None` if the shape is the prohibited value.
+  #[inline]
+  #[must_use]
+  pub const fn obj_dimensions(&self) -> Option<(u8, u8)> {
+    obj_dimensions(self.attr0.obj_shape(), self.attr1.obj_size())
+  }
+
+  /// The area the object covers on screen, as `(x, y, width, height)`.
+  ///
+  /// The x coordinate is a signed 9-bit value. The y coordinate is only 8
+  /// bits and wraps around the bottom of the screen, so values of 160 and up
+  /// are treated as being above the top of the screen. A double size affine
+  /// object covers twice the width and height of its sprite.
+  ///
+  /// This doesn't check if the object is disabled. An object with the
+  /// prohibited shape has a width and height of 0.
+  #[inline]
+  #[must_use]
+  pub const fn bounding_box(&self) -> (i16, i16, u8, u8) {
+    let x = self.attr1.x_coordinate() as i16;
+    let x = if x >= 256 { x - 512 } else { x };
+    let y = self.attr0.y_coordinate() as i16;
+    let y = if y >= 160 { y - 256 } else { y };
+    let (w, h) = match self.obj_dimensions() {
+      Some(dimensions) => dimensions,
+      None => (0, 0),
+    };
+    match self.attr0.obj_display_mode() {
+      ObjDisplayMode::DoubleSizeAffine => (x, y, w * 2, h * 2),
+      _ => (x, y, w, h),
+    }
+  }
+
+  /// Makes this an affine object that uses the parameters in `slot`, 0 through
+  /// 31.
+  ///
+  /// A double size affine object stays double size, anything else becomes
+  /// a normal size affine object. `matrix` is given back unchanged, to be
+  /// written to OAM at the [`obj_affine_offsets`] of the same slot.
+  #[inline]
+  pub const fn bind_affine(
+    &mut self, slot: u8, matrix: ObjAffineMatrix,
+  ) -> ObjAffineMatrix {
+    match self.attr0.obj_display_mode() {
+      ObjDisplayMode::DoubleSizeAffine => (),
+      _ => self.attr0.set_obj_display_mode(ObjDisplayMode::Affine),
+    }
+    self.attr1.set_affine_param(slot as u16 & 0b1_1111);
+    matrix
+  }
+}
+
+/// An object affine parameter, `PA` through `PD`.
+///
+/// These use the same 8.8 fixed point format as the background affine
+/// parameters.
+pub type ObjAffineParameter = AffineParameter;
+
+/// The four affine parameters used by affine objects that select a given
+/// `affine_param` index.
+pub type ObjAffineMatrix = AffineMatrix;
+
+/// The byte offsets within OAM of `PA`, `PB`, `PC`, and `PD` for an affine
+/// parameter index, 0 through 31.
+///
+/// The parameters are interleaved with the object attributes: each one is the
+/// last 2 bytes of an 8 byte object entry. So one set of parameters is spread
+/// over 4 object entries, with a stride of 8 bytes. Parameter set `n` uses
+/// objects `4n` through `4n + 3`.
+#[inline]
+#[must_use]
+pub const fn obj_affine_offsets(index: u8) -> [usize; 4] {
+  let base = 32 * (index as usize & 0b1_1111) + 6;
+  [base, base + 8, base + 16, base + 24]
+}
+
+pub mod sound;
+
 pub mod timer;
 
 pub mod dma;
 
+pub mod serial;
+
 bitstruct_newtype! {
   /// Indicates which buttons are pressed. A button with a value of 0 is pressed, and a value of 1 is released.
   /// It is recommended that you check these during vblank interrupts. It's an effective way to filter out bounce.
   KeyInputLowActive(u16) {
     // A button.
-    [0: a_released, set_a_released],
+    [0: a_released, set_a_released, toggle_a_released],
     // B button.
-    [1: b_released, set_b_released],
+    [1: b_released, set_b_released, toggle_b_released],
     // Select button.
-    [2: select_released, set_select_released],
+    [2: select_released, set_select_released, toggle_select_released],
     // Start button.
-    [3: start_released, set_start_released],
+    [3: start_released, set_start_released, toggle_start_released],
     // Right direction button.
-    [4: right_released, set_right_released],
+    [4: right_released, set_right_released, toggle_right_released],
     // Left direction button.
-    [5: left_released, set_left_released],
+    [5: left_released, set_left_released, toggle_left_released],
     // Up direction button.
-    [6: up_released, set_up_released],
+    [6: up_released, set_up_released, toggle_up_released],
     // Down direction button.
-    [7: down_released, set_down_released],
+    [7: down_released, set_down_released, toggle_down_released],
     // Right bumper.
-    [8: r_released, set_r_released],
+    [8: r_released, set_r_released, toggle_r_released],
     // Left bumper.
-    [9: l_released, set_l_released],
+    [9: l_released, set_l_released, toggle_l_released],
+  }
+}
+
+impl KeyInputLowActive {
+  /// Releases both directions of any opposite pair that's pressed together.
+  ///
+  /// A d-pad can't really press left and right (or up and down) at the same
+  /// time, but a worn pad or an emulator keyboard can report it, and game
+  /// logic often doesn't expect it. This is the usual simultaneous opposite
+  /// direction (SOD) cleanup: when both of a pair are pressed, neither one
+  /// counts as pressed. The other pair and all the buttons are untouched.
+  #[inline]
+  #[must_use]
+  pub const fn filter_sod(self) -> Self {
+    let mut keys = self;
+    if !self.left_released() && !self.right_released() {
+      keys.set_left_released(true);
+      keys.set_right_released(true);
+    }
+    if !self.up_released() && !self.down_released() {
+      keys.set_up_released(true);
+      keys.set_down_released(true);
+    }
+    keys
+  }
+}
+
+bitstruct_newtype! {
+  /// Indicates which buttons are pressed, using 1 for pressed and 0 for
+  /// released.
+  ///
+  /// This is the inverse of the [`KeyInputLowActive`] value that the hardware
+  /// gives, and converts to and from it with `From`.
+  KeyState(u16) {
+    /// A button.
+    [0: a_pressed, set_a_pressed, toggle_a_pressed],
+    /// B button.
+    [1: b_pressed, set_b_pressed, toggle_b_pressed],
+    /// Select button.
+    [2: select_pressed, set_select_pressed, toggle_select_pressed],
+    /// Start button.
+    [3: start_pressed, set_start_pressed, toggle_start_pressed],
+    /// Right direction button.
+    [4: right_pressed, set_right_pressed, toggle_right_pressed],
+    /// Left direction button.
+    [5: left_pressed, set_left_pressed, toggle_left_pressed],
+    /// Up direction button.
+    [6: up_pressed, set_up_pressed, toggle_up_pressed],
+    /// Down direction button.
+    [7: down_pressed, set_down_pressed, toggle_down_pressed],
+    /// Right bumper.
+    [8: r_pressed, set_r_pressed, toggle_r_pressed],
+    /// Left bumper.
+    [9: l_pressed, set_l_pressed, toggle_l_pressed],
+  }
+}
+
+impl From<KeyInputLowActive> for KeyState {
+  #[inline]
+  fn from(keys: KeyInputLowActive) -> Self {
+    Self(!keys.0 & 0b11_1111_1111)
+  }
+}
+
+impl From<KeyState> for KeyInputLowActive {
+  #[inline]
+  fn from(keys: KeyState) -> Self {
+    Self(!keys.0 & 0b11_1111_1111)
   }
 }
 
@@ -431,31 +2643,49 @@ bitstruct_newtype! {
   /// Setting a field to 1 will enable that key to trigger the interrupt.
   KeyInterruptBits(u16) {
     /// Enable the A button.
-    [0: a_selected, set_a_selected],
+    [0: a_selected, set_a_selected, toggle_a_selected],
     /// Enable the B button.
-    [1: b_selected, set_b_selected],
+    [1: b_selected, set_b_selected, toggle_b_selected],
     /// Enable the select button.
-    [2: select_selected, set_select_selected],
+    [2: select_selected, set_select_selected, toggle_select_selected],
     /// Enable the start button.
-    [3: start_selected, set_start_selected],
+    [3: start_selected, set_start_selected, toggle_start_selected],
     /// Enable the right directional pad button.
-    [4: right_selected, set_right_selected],
+    [4: right_selected, set_right_selected, toggle_right_selected],
     /// Enable the left directional pad button.
-    [5: left_selected, set_left_selected],
+    [5: left_selected, set_left_selected, toggle_left_selected],
     /// Enable the up directional pad button.
-    [6: up_selected, set_up_selected],
+    [6: up_selected, set_up_selected, toggle_up_selected],
     /// Enable the down directional pad button.
-    [7: down_selected, set_down_selected],
+    [7: down_selected, set_down_selected, toggle_down_selected],
     /// Enable the right bumper button.
-    [8: r_selected, set_r_selected],
+    [8: r_selected, set_r_selected, toggle_r_selected],
     /// Enable the left bumper button.
-    [9: l_selected, set_l_selected],
+    [9: l_selected, set_l_selected, toggle_l_selected],
     /// Enable key interrupts.
-    [14: key_interrupts_enabled, set_key_interrupts_enabled],
+    [14: key_interrupts_enabled, set_key_interrupts_enabled, toggle_key_interrupts_enabled],
     /// When set to 1, all enabled buttons must be pressed to trigger the
     /// interrupt.
     /// When set to 0, pressing any enabled button will trigger the interrupt.
-    [15: interrupt_requires_all_bits, set_interrupt_requires_all_bits],
+    [15: interrupt_requires_all_bits, set_interrupt_requires_all_bits, toggle_interrupt_requires_all_bits],
+  }
+}
+
+impl KeyInterruptBits {
+  /// All ten of the key select bits as a single mask, with the A button in
+  /// bit 0 through the left bumper in bit 9.
+  #[inline]
+  #[must_use]
+  pub const fn selected_keys(self) -> u16 {
+    bit_get!(self.0, 0x3FF) as u16
+  }
+
+  /// Sets all ten key select bits from a mask, as given by `selected_keys`.
+  ///
+  /// Bits of `mask` above bit 9 are ignored.
+  #[inline]
+  pub const fn set_selected_keys(&mut self, mask: u16) {
+    *self = Self(bit_set!(self.0, 0x3FF, mask) as u16);
   }
 }
 
@@ -473,34 +2703,91 @@ bitstruct_newtype! {
   ///     0-bit, but that's really how it works.
   InterruptFlagBits(u16) {
     /// Vertical Blank Interrupt.
-    [0: vblank, set_vblank],
+    [0: vblank, set_vblank, toggle_vblank],
     /// Horizontal Blank Interrupt.
-    [1: hblank, set_hblank],
+    [1: hblank, set_hblank, toggle_hblank],
     /// Vertical count match Interrupt.
-    [2: vcount_match, set_vcount_match],
+    [2: vcount_match, set_vcount_match, toggle_vcount_match],
     /// Timer 0 overflow.
-    [3: timer0, set_timer0],
+    [3: timer0, set_timer0, toggle_timer0],
     /// Timer 1 overflow.
-    [4: timer1, set_timer1],
+    [4: timer1, set_timer1, toggle_timer1],
     /// timer 2 overflow.
-    [5: timer2, set_timer2],
+    [5: timer2, set_timer2, toggle_timer2],
     /// Timer 3 overflow.
-    [6: timer3, set_timer3],
+    [6: timer3, set_timer3, toggle_timer3],
     /// Serial Communication
-    [7: serial_communication, set_serial_communication],
+    [7: serial_communication, set_serial_communication, toggle_serial_communication],
     /// Direct Memory Access channel 0
-    [8: dma0, set_dma0],
+    [8: dma0, set_dma0, toggle_dma0],
     /// Direct Memory Access channel 1
-    [9: dma1, set_dma1],
+    [9: dma1, set_dma1, toggle_dma1],
     /// Direct Memory Access channel 2
-    [10: dma2, set_dma2],
+    [10: dma2, set_dma2, toggle_dma2],
     /// Direct Memory Access channel 3
-    [11: dma3, set_dma3],
+    [11: dma3, set_dma3, toggle_dma3],
     /// Keypad
-    [12: keypad, set_keypad],
+    [12: keypad, set_keypad, toggle_keypad],
     /// This interrupt is generated by hardware inside the game pak itself,
     /// such as a co-processor, camera, or other hardware.
-    [13: game_pak, set_game_pak],
+    [13: game_pak, set_game_pak, toggle_game_pak],
+  }
+}
+
+bitstruct_newtype! {
+  /// The interrupt master enable register (`IME`).
+  ///
+  /// Only bit 0 is meaningful. When it's clear no interrupts are handled at
+  /// all, no matter what's set in `IE`.
+  InterruptMasterEnable(u16) {
+    /// If interrupts are handled at all.
+    [0: enabled, set_enabled, toggle_enabled],
+  }
+}
+
+impl InterruptMasterEnable {
+  /// Interrupts are handled according to `IE`.
+  ///
+  /// This and [`DISABLED`](Self::DISABLED) are constants rather than
+  /// `enabled()` and `disabled()` constructors, because `enabled` is already
+  /// the name of the bit 0 getter.
+  pub const ENABLED: Self = Self(1);
+
+  /// No interrupts are handled.
+  pub const DISABLED: Self = Self(0);
+}
+
+bitstruct_newtype! {
+  /// The post boot flag register (`POSTFLG`).
+  ///
+  /// The BIOS sets this after the first boot, so it can tell a cold boot
+  /// apart from a soft reset.
+  PostBootFlag(u8) {
+    /// Clear on the first boot, set once the BIOS has finished booting.
+    [0: first_boot, set_first_boot, toggle_first_boot],
+  }
+}
+
+const_enum! {
+  /// The low power mode entered by writing to `HALTCNT`.
+  PowerDownMode(u8) {
+    /// The CPU stops until an interrupt is requested. Everything else keeps
+    /// running.
+    Halt(0 << 7),
+    /// Most of the hardware stops until a keypad, serial, or game pak
+    /// interrupt is requested.
+    Stop(1 << 7),
+  }
+}
+
+bitstruct_newtype! {
+  /// The low power mode control register (`HALTCNT`).
+  ///
+  /// This register is write-only. Writing it immediately enters the selected
+  /// mode.
+  PowerDownControl(u8) {
+    /// The mode to enter.
+    [7-7 => PowerDownMode: mode, set_mode],
   }
 }
 
@@ -569,15 +2856,15 @@ bitstruct_newtype! {
     /// Wait State 0 First Access timing.
     [2-3 => Rom0WaitControlCycles: wait0_first_access, set_wait0_first_access],
     /// Wait State 0 Second Access timing.
-    [4: wait0_second_access_1cycle, set_wait0_second_access_1cycle],
+    [4: wait0_second_access_1cycle, set_wait0_second_access_1cycle, toggle_wait0_second_access_1cycle],
     /// Wait State 1 First Access timing.
     [5-6 => Rom1WaitControlCycles: wait1_first_access, set_wait1_first_access],
     /// Wait State 1 Second Access timing.
-    [7: wait1_second_access_1cycle, set_wait1_second_access_1cycle],
+    [7: wait1_second_access_1cycle, set_wait1_second_access_1cycle, toggle_wait1_second_access_1cycle],
     /// Wait State 2 First Access timing.
     [8-9 => Rom2WaitControlCycles: wait2_first_access, set_wait2_first_access],
     /// Wait State 2 Second Access timing.
-    [10: wait2_second_access_1cycle, set_wait2_second_access_1cycle],
+    [10: wait2_second_access_1cycle, set_wait2_second_access_1cycle, toggle_wait2_second_access_1cycle],
     /// PHI Terminal Output speed. Usage documentation is unclear. Is likely
     /// for strange Nintendo peripherals.
     [11-12 => PhiTerminalOutput: phi_terminal, set_phi_terminal],
@@ -585,6 +2872,1187 @@ bitstruct_newtype! {
     /// fetch the next CPU instruction before it is needed, thus brining the
     /// wait down to zero cycles. This, howerver, is dependent on the bus being
     /// free at some point long enough to do this.
-    [14: game_pak_prefetch_enabled, set_game_pak_prefetch_enabled],
+    [14: game_pak_prefetch_enabled, set_game_pak_prefetch_enabled, toggle_game_pak_prefetch_enabled],
+  }
+}
+
+const_enum! {
+  /// A region of the game pak address space with its own wait settings.
+  GamepakRegion(u8) {
+    /// Wait state 0, at 0x08000000.
+    WaitState0(0),
+    /// Wait state 1, at 0x0A000000.
+    WaitState1(1),
+    /// Wait state 2, at 0x0C000000.
+    WaitState2(2),
+    /// Game pak SRAM, at 0x0E000000.
+    Sram(3),
+  }
+}
+
+impl GamepakRegion {
+  /// The address that the region starts at.
+  #[inline]
+  #[must_use]
+  pub const fn base_address(self) -> usize {
+    match self {
+      GamepakRegion::WaitState0 => 0x0800_0000,
+      GamepakRegion::WaitState1 => 0x0A00_0000,
+      GamepakRegion::Sram => 0x0E00_0000,
+      _ => 0x0C00_0000,
+    }
+  }
+}
+
+/// Converts a 2-bit first access setting into its number of wait cycles.
+const fn first_access_wait_cycles(setting: u16) -> u8 {
+  match setting & 0b11 {
+    0 => 4,
+    1 => 3,
+    2 => 2,
+    _ => 8,
+  }
+}
+
+impl WaitControlSetting {
+  /// The value at power on, which the BIOS leaves unchanged: the slowest
+  /// setting for every region, with the prefetch buffer disabled.
+  #[inline]
+  #[must_use]
+  pub const fn hardware_reset() -> Self {
+    Self(0)
+  }
+
+  /// The fast setting that most games use, `0x4317`.
+  ///
+  /// Wait state 0 takes 3 cycles for a first access and 1 for a second
+  /// access, with the prefetch buffer enabled. SRAM takes 8 cycles, and the
+  /// other wait states are left slow.
+  #[inline]
+  #[must_use]
+  pub const fn fast_gamepak() -> Self {
+    Self::builder()
+      .sram(SramWaitControlCycles::_8)
+      .rom0(Rom0WaitControlCycles::_3, true)
+      .rom2(Rom2WaitControlCycles::_8, false)
+      .prefetch(true)
+  }
+
+  /// If this is different from the value the BIOS leaves the register at, see
+  /// [`hardware_reset`](Self::hardware_reset).
+  #[inline]
+  #[must_use]
+  pub const fn differs_from_bios_default(self) -> bool {
+    self.0 != Self::hardware_reset().0
+  }
+
+  /// The fields that are different from the value the BIOS leaves the register
+  /// at, in declaration order.
+  #[cfg(feature = "alloc")]
+  #[must_use]
+  pub fn diff_from_bios_default(self) -> alloc::vec::Vec<RegisterField> {
+    changed_fields(Self::hardware_reset(), self)
+  }
+
+  /// Starts building a setting, from the all-zero value.
+  ///
+  /// The all-zero value is the slowest setting for every region, which is also
+  /// the value at power on. Chain the other builder methods to change it:
+  ///
+  /// ```
+  /// # use gba_types::*;
+  /// const WAITCNT: WaitControlSetting = WaitControlSetting::builder()
+  ///   .rom0(Rom0WaitControlCycles::_3, true)
+  ///   .sram(SramWaitControlCycles::_8)
+  ///   .prefetch(true);
+  /// ```
+  #[inline]
+  #[must_use]
+  pub const fn builder() -> Self {
+    Self(0)
+  }
+
+  /// Sets the wait state 0 first access timing, and if its second access
+  /// takes 1 cycle.
+  #[inline]
+  #[must_use]
+  pub const fn rom0(
+    mut self, first_access: Rom0WaitControlCycles, second_1cycle: bool,
+  ) -> Self {
+    self.set_wait0_first_access(first_access);
+    self.set_wait0_second_access_1cycle(second_1cycle);
+    self
+  }
+
+  /// Sets the wait state 1 first access timing, and if its second access
+  /// takes 1 cycle.
+  #[inline]
+  #[must_use]
+  pub const fn rom1(
+    mut self, first_access: Rom1WaitControlCycles, second_1cycle: bool,
+  ) -> Self {
+    self.set_wait1_first_access(first_access);
+    self.set_wait1_second_access_1cycle(second_1cycle);
+    self
+  }
+
+  /// Sets the wait state 2 first access timing, and if its second access
+  /// takes 1 cycle.
+  #[inline]
+  #[must_use]
+  pub const fn rom2(
+    mut self, first_access: Rom2WaitControlCycles, second_1cycle: bool,
+  ) -> Self {
+    self.set_wait2_first_access(first_access);
+    self.set_wait2_second_access_1cycle(second_1cycle);
+    self
+  }
+
+  /// Sets the SRAM wait control.
+  #[inline]
+  #[must_use]
+  pub const fn sram(mut self, wait: SramWaitControlCycles) -> Self {
+    self.set_sram_wait(wait);
+    self
+  }
+
+  /// Sets if the game pak prefetch buffer is enabled.
+  #[inline]
+  #[must_use]
+  pub const fn prefetch(mut self, enabled: bool) -> Self {
+    self.set_game_pak_prefetch_enabled(enabled);
+    self
+  }
+
+  /// The number of wait cycles for a non-sequential access to the region.
+  ///
+  /// This is just the wait, so the access itself takes one more cycle.
+  #[inline]
+  #[must_use]
+  pub const fn nonsequential_access_cycles(self, region: GamepakRegion) -> u8 {
+    match region {
+      GamepakRegion::Sram => first_access_wait_cycles(self.sram_wait().0),
+      GamepakRegion::WaitState0 => {
+        first_access_wait_cycles(self.wait0_first_access().0 >> 2)
+      }
+      GamepakRegion::WaitState1 => {
+        first_access_wait_cycles(self.wait1_first_access().0 >> 5)
+      }
+      _ => first_access_wait_cycles(self.wait2_first_access().0 >> 8),
+    }
+  }
+
+  /// The number of wait cycles for a sequential access to the region.
+  ///
+  /// This is just the wait, so the access itself takes one more cycle. SRAM
+  /// has no sequential access, so it's the same as a non-sequential access.
+  #[inline]
+  #[must_use]
+  pub const fn sequential_access_cycles(self, region: GamepakRegion) -> u8 {
+    match region {
+      GamepakRegion::Sram => self.nonsequential_access_cycles(region),
+      GamepakRegion::WaitState0 => {
+        if self.wait0_second_access_1cycle() {
+          1
+        } else {
+          2
+        }
+      }
+      GamepakRegion::WaitState1 => {
+        if self.wait1_second_access_1cycle() {
+          1
+        } else {
+          4
+        }
+      }
+      _ => {
+        if self.wait2_second_access_1cycle() {
+          1
+        } else {
+          8
+        }
+      }
+    }
+  }
+
+  /// The best case number of wait cycles for a sequential instruction fetch
+  /// from the region.
+  ///
+  /// When the prefetch buffer is enabled, ROM fetches can have no wait at all,
+  /// so this gives 0. That's only the best case though: the prefetch buffer
+  /// can only fill while the game pak bus is otherwise free, so code that also
+  /// reads ROM data will often still pay the sequential cost. SRAM is never
+  /// prefetched.
+  #[inline]
+  #[must_use]
+  pub const fn best_case_sequential_fetch_cycles(
+    self, region: GamepakRegion,
+  ) -> u8 {
+    match region {
+      GamepakRegion::Sram => self.sequential_access_cycles(region),
+      _ if self.game_pak_prefetch_enabled() => 0,
+      _ => self.sequential_access_cycles(region),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn window_content_presets() {
+    assert_eq!(WindowContentSetting::show_all().0, 0b11_1111);
+    assert_eq!(WindowContentSetting::show_none().0, 0);
+    let objects = WindowContentSetting::objects_only();
+    assert_eq!(objects.0, 0b01_0000);
+    assert!(objects.display_obj());
+    assert!(!objects.display_bg0());
+  }
+
+  #[test]
+  fn background_scroll_wraps_past_512() {
+    let scroll = BackgroundScroll::from_pixels(515, 1030);
+    assert_eq!((scroll.h.offset(), scroll.v.offset()), (3, 6));
+    let mut scroll = BackgroundScroll::from_pixels(510, 500);
+    scroll.scroll_by(5, 20);
+    assert_eq!((scroll.h.offset(), scroll.v.offset()), (3, 8));
+    scroll.scroll_by(-5, -10);
+    assert_eq!((scroll.h.offset(), scroll.v.offset()), (510, 510));
+  }
+
+  #[test]
+  fn obj_vram_mapping_tile_stride() {
+    let mut display = DisplayControlSetting::default();
+    assert_eq!(display.obj_vram_mapping(), ObjVramMapping::TwoDimensional);
+    assert_eq!(display.tile_stride(4), 32);
+    display.set_obj_vram_is_1d(true);
+    assert_eq!(display.obj_vram_mapping(), ObjVramMapping::OneDimensional);
+    assert_eq!(display.tile_stride(4), 4);
+    display.set_obj_vram_mapping(ObjVramMapping::TwoDimensional);
+    assert!(!display.obj_vram_is_1d());
+    let bit6 =
+      DisplayControlSetting::FIELDS.iter().filter(|f| f.mask == 1 << 6);
+    assert_eq!(bit6.count(), 1);
+  }
+
+  #[test]
+  fn toggle_flips_a_single_bit() {
+    let original = DisplayControlSetting::sprites_mode0();
+    let mut display = original;
+    display.toggle_show_frame1();
+    assert!(display.show_frame1());
+    assert_eq!(display.0 ^ original.0, 1 << 4);
+    display.toggle_show_frame1();
+    assert_eq!(display, original);
+  }
+
+  #[test]
+  fn toggle_is_optional_in_the_field_list() {
+    bitstruct_newtype! {
+      ToggleTest(u8) {
+        [0: plain, set_plain],
+        [1: flippable, set_flippable, toggle_flippable],
+      }
+    }
+    let mut value = ToggleTest(0);
+    value.set_plain(true);
+    value.toggle_flippable();
+    assert!(value.plain() && value.flippable());
+    value.set_flippable(false);
+    assert_eq!(value.0, 0b01);
+  }
+
+  #[test]
+  fn wait_control_access_cycles() {
+    let waitcnt = WaitControlSetting(0x4317);
+    assert_eq!(waitcnt.nonsequential_access_cycles(GamepakRegion::Sram), 8);
+    assert_eq!(
+      waitcnt.nonsequential_access_cycles(GamepakRegion::WaitState0),
+      3
+    );
+    assert_eq!(waitcnt.sequential_access_cycles(GamepakRegion::WaitState0), 1);
+    assert_eq!(
+      waitcnt.nonsequential_access_cycles(GamepakRegion::WaitState1),
+      4
+    );
+    assert_eq!(waitcnt.sequential_access_cycles(GamepakRegion::WaitState1), 4);
+    assert_eq!(
+      waitcnt.nonsequential_access_cycles(GamepakRegion::WaitState2),
+      8
+    );
+    assert_eq!(waitcnt.sequential_access_cycles(GamepakRegion::WaitState2), 8);
+    assert_eq!(waitcnt.sequential_access_cycles(GamepakRegion::Sram), 8);
+  }
+
+  #[test]
+  fn palette256_expand_into() {
+    let mut palette = Palette256::default();
+    palette.0[1] = Color::RED;
+    palette.0[200] = Color::BLUE;
+    let mut out = [Color::WHITE; 4];
+    palette.expand_into(&[200, 1, 0], &mut out);
+    assert_eq!(out, [Color::BLUE, Color::RED, Color::BLACK, Color::WHITE]);
+  }
+
+  #[test]
+  fn palette_transparent_entry() {
+    let mut palette16 = Palette16::default();
+    palette16.set_transparent(Color::RED);
+    assert_eq!(palette16.transparent(), Color::RED);
+    assert_eq!(palette16.0[0], Color::RED);
+    let mut palette256 = Palette256::default();
+    palette256.set_transparent(Color::GREEN);
+    assert_eq!(palette256.transparent(), Color::GREEN);
+    assert_eq!(palette256.0[1], Color::BLACK);
+  }
+
+  #[test]
+  fn video_mode_framebuffer_bpp() {
+    assert_eq!(VideoMode::_0.framebuffer_bpp(), None);
+    assert_eq!(VideoMode::_3.framebuffer_bpp(), Some(16));
+    assert_eq!(VideoMode::_4.framebuffer_bpp(), Some(8));
+    assert_eq!(VideoMode::_5.framebuffer_bpp(), Some(16));
+  }
+
+  #[test]
+  fn cycle_wraps_around() {
+    assert_eq!(BlendEffect::NoEffect.cycle(), BlendEffect::AlphaBlend);
+    assert_eq!(
+      BlendEffect::AlphaBlend.cycle(),
+      BlendEffect::BrightnessIncrease
+    );
+    assert_eq!(
+      BlendEffect::BrightnessIncrease.cycle(),
+      BlendEffect::BrightnessDecrease
+    );
+    assert_eq!(BlendEffect::BrightnessDecrease.cycle(), BlendEffect::NoEffect);
+    assert_eq!(BlendEffect(1).cycle(), BlendEffect::NoEffect);
+  }
+
+  #[test]
+  fn key_interrupt_selected_keys() {
+    let mut bits = KeyInterruptBits::default();
+    bits.set_key_interrupts_enabled(true);
+    bits.set_selected_keys(0b1001);
+    assert!(bits.a_selected() && bits.start_selected());
+    assert!(!bits.b_selected() && !bits.select_selected());
+    assert_eq!(bits.selected_keys(), 0b1001);
+    assert!(bits.key_interrupts_enabled());
+    assert_eq!(bits.0, 1 << 14 | 0b1001);
+    bits.set_selected_keys(u16::MAX);
+    assert_eq!(bits.0, 1 << 14 | 0x3FF);
+    let overlapping =
+      KeyInterruptBits::FIELDS.iter().filter(|f| f.mask & 1 != 0);
+    assert_eq!(overlapping.count(), 1);
+  }
+
+  #[test]
+  fn palette_u16_slice_round_trip() {
+    let raw = [0x7FFF, 0x001F, 0x03E0];
+    let palette16 = Palette16::from_u16_slice(&raw);
+    assert_eq!(&palette16.as_u16_slice()[..3], &raw);
+    assert_eq!(palette16.as_u16_slice()[3..], [0; 13]);
+    let palette256 = Palette256::from_u16_slice(&raw);
+    assert_eq!(palette256.as_u16_slice().len(), 256);
+    assert_eq!(&palette256.as_u16_slice()[..3], &raw);
+    let long = [0x1234; 20];
+    assert_eq!(Palette16::from_u16_slice(&long).as_u16_slice(), &long[..16]);
+  }
+
+  #[test]
+  fn background_max_tile_id() {
+    let mut bg = BackgroundControlSetting::default();
+    assert_eq!(bg.max_tile_id(), 1023);
+    bg.set_base_charblock(3);
+    assert_eq!(bg.max_tile_id(), 511);
+    bg.set_is_8bpp(true);
+    assert_eq!(bg.max_tile_id(), 255);
+    bg.set_base_charblock(0);
+    assert_eq!(bg.max_tile_id(), 1023);
+    bg.set_base_charblock(2);
+    assert_eq!(bg.max_tile_id(), 511);
+  }
+
+  #[test]
+  fn color_channels_round_trip() {
+    let color = Color::from_channels([31, 8, 1]);
+    assert_eq!(color.channels(), [31, 8, 1]);
+    assert_eq!(Color::from_channels(color.channels()), color);
+    assert_eq!(Color::BLUE.channels(), [0, 0, 31]);
+    assert_eq!(Color::from_channels([0, 0, 0b10_0001]).channels(), [0, 0, 1]);
+  }
+
+  #[test]
+  fn every_video_mode_matches_its_capabilities() {
+    assert_eq!(VideoMode::ALL.len(), 6);
+    for &mode in VideoMode::ALL {
+      let mut display = DisplayControlSetting::sprites_mode0();
+      display.set_video_mode(mode);
+      assert_eq!(display.active_backgrounds(), [false; 4]);
+      let available = mode.backgrounds();
+      display.set_display_bg0(available[0]);
+      display.set_display_bg1(available[1]);
+      display.set_display_bg2(available[2]);
+      display.set_display_bg3(available[3]);
+      assert_eq!(display.validate(), Ok(()), "{:?}", mode);
+      display.set_display_bg0(true);
+      display.set_display_bg1(true);
+      display.set_display_bg2(true);
+      display.set_display_bg3(true);
+      assert_eq!(display.active_backgrounds(), available, "{:?}", mode);
+      let count = available.iter().filter(|&&bg| bg).count();
+      assert_eq!(usize::from(display.available_bg_count()), count);
+      let bitmap = display.video_mode().is_bitmap();
+      assert_eq!(mode.framebuffer_bpp().is_some(), bitmap, "{:?}", mode);
+      if bitmap {
+        assert_eq!(available, [false, false, true, false], "{:?}", mode);
+      }
+    }
+    let bitmaps = VideoMode::ALL.iter().filter(|mode| mode.is_bitmap());
+    assert_eq!(bitmaps.count(), 3);
+  }
+
+  #[test]
+  fn prefetch_best_case_fetch() {
+    let mut waitcnt = WaitControlSetting(0x4317);
+    assert!(waitcnt.game_pak_prefetch_enabled());
+    let ws0 = GamepakRegion::WaitState0;
+    assert_eq!(waitcnt.best_case_sequential_fetch_cycles(ws0), 0);
+    let ws1 = GamepakRegion::WaitState1;
+    assert_eq!(waitcnt.best_case_sequential_fetch_cycles(ws1), 0);
+    let sram = GamepakRegion::Sram;
+    assert_eq!(waitcnt.best_case_sequential_fetch_cycles(sram), 8);
+    waitcnt.set_game_pak_prefetch_enabled(false);
+    assert_eq!(waitcnt.best_case_sequential_fetch_cycles(ws0), 1);
+    assert_eq!(waitcnt.best_case_sequential_fetch_cycles(ws1), 4);
+    assert_eq!(waitcnt.best_case_sequential_fetch_cycles(sram), 8);
+  }
+
+  #[test]
+  fn key_state_round_trip() {
+    let idle = KeyInputLowActive(0b11_1111_1111);
+    let keys: KeyState = idle.into();
+    assert_eq!(keys.0, 0);
+    let raw = KeyInputLowActive(0b11_1111_1110);
+    let keys: KeyState = raw.into();
+    assert!(keys.a_pressed() && !keys.b_pressed());
+    assert_eq!(KeyInputLowActive::from(keys).0, raw.0);
+    let mut keys = KeyState::default();
+    keys.set_l_pressed(true);
+    keys.set_up_pressed(true);
+    assert_eq!(KeyState::from(KeyInputLowActive::from(keys)).0, keys.0);
+  }
+
+  #[test]
+  fn blend_target_by_layer() {
+    let mut blend = ColorBlendControlSetting::default();
+    blend.set_first_target(BlendLayer::Obj, true);
+    assert!(blend.first_target_obj());
+    assert!(blend.is_first_target(BlendLayer::Obj));
+    assert!(!blend.is_second_target(BlendLayer::Obj));
+    assert_eq!(blend.0, 1 << 4);
+    blend.set_second_target(BlendLayer::Backdrop, true);
+    assert!(blend.second_target_backdrop());
+    assert_eq!(blend.0, 1 << 4 | 1 << 13);
+    blend.set_first_target(BlendLayer::Obj, false);
+    assert_eq!(blend.0, 1 << 13);
+  }
+
+  #[test]
+  fn color_pair_round_trip() {
+    let pair = Color::pack_pair(Color::RED, Color::BLUE);
+    assert_eq!(pair, 0x7C00_001F);
+    assert_eq!(&pair.to_le_bytes()[..2], &Color::RED.0.to_le_bytes());
+    assert_eq!(Color::unpack_pair(pair), (Color::RED, Color::BLUE));
+  }
+
+  #[test]
+  fn window_presets_special_effect_bit() {
+    assert!(WindowContentSetting::show_all().allows_blending());
+    assert!(!WindowContentSetting::show_none().allows_blending());
+    assert!(!WindowContentSetting::objects_only().allows_blending());
+    let mut content = WindowContentSetting::objects_only();
+    content.set_display_special_effect(true);
+    assert!(content.allows_blending());
+    assert_eq!(content.0, 1 << 4 | 1 << 5);
+  }
+
+  #[test]
+  fn obj_attr2_configure_4bpp() {
+    let attr = ObjAttr2::configure_4bpp(1023, 15, 3).unwrap();
+    assert_eq!(attr.0, 0xFFFF);
+    let attr = ObjAttr2::configure_4bpp(5, 2, 1).unwrap();
+    assert_eq!(
+      (attr.base_tile_id(), attr.palbank(), attr.priority()),
+      (5, 2, 1)
+    );
+    assert_eq!(attr.0, 2 << 12 | 1 << 10 | 5);
+    assert_eq!(ObjAttr2::configure_4bpp(1024, 0, 0), None);
+    assert_eq!(ObjAttr2::configure_4bpp(0, 16, 0), None);
+    assert_eq!(ObjAttr2::configure_4bpp(0, 0, 4), None);
+  }
+
+  #[test]
+  fn forced_blank_builder() {
+    let display = DisplayControlSetting::sprites_mode0();
+    let blanked = display.with_forced_blank(true);
+    assert!(blanked.forced_blank());
+    assert_eq!(blanked.0, display.0 | 1 << 7);
+    assert_eq!(blanked.with_forced_blank(false), display);
+  }
+
+  #[test]
+  fn register_volatile_access() {
+    let mut fake_dispcnt: u16 = 0x0403;
+    let display =
+      unsafe { DisplayControlSetting::read_volatile_from(&fake_dispcnt) };
+    assert_eq!(display.video_mode(), VideoMode::_3);
+    assert!(display.display_bg2());
+    let display = display.with_forced_blank(true);
+    unsafe { display.write_volatile_to(&mut fake_dispcnt) };
+    assert_eq!(fake_dispcnt, 0x0483);
+  }
+
+  #[test]
+  fn palette_macro_colors() {
+    const PRIMARIES: Palette16 =
+      palette![Palette16; (31, 0, 0), (0, 31, 0), (0, 0, 31),];
+    assert_eq!(PRIMARIES.0[..3], [Color::RED, Color::GREEN, Color::BLUE]);
+    assert!(PRIMARIES.0[3..].iter().all(|&c| c == Color(0)));
+    static GRAYS: Palette256 =
+      palette![Palette256; (0, 0, 0), (16, 16, 16), (31, 31, 31)];
+    assert_eq!(GRAYS.0[1], Color::from_rgb(16, 16, 16));
+    assert_eq!(GRAYS.0[2], Color::WHITE);
+    assert_eq!(GRAYS.0[3], Color(0));
+    let runtime = palette![Palette16; (1, 2, 3)];
+    assert_eq!(runtime.0[0].channels(), [1, 2, 3]);
+  }
+
+  #[test]
+  fn rotate_channels_three_times() {
+    let color = Color::from_channels([31, 10, 2]);
+    assert_eq!(color.rotate_channels(1).channels(), [2, 31, 10]);
+    assert_eq!(color.rotate_channels(2).channels(), [10, 2, 31]);
+    let thrice = color.rotate_channels(1).rotate_channels(1).rotate_channels(1);
+    assert_eq!(thrice, color);
+    assert_eq!(color.rotate_channels(3), color);
+    assert_eq!(Color::RED.rotate_channels(1), Color::GREEN);
+  }
+
+  #[test]
+  fn wait_control_builder_profile() {
+    let waitcnt = WaitControlSetting::builder()
+      .rom1(Rom1WaitControlCycles::_2, true)
+      .sram(SramWaitControlCycles::_3)
+      .prefetch(false);
+    assert_eq!(waitcnt.0, 0b1100_0001);
+    assert_eq!(
+      waitcnt.nonsequential_access_cycles(GamepakRegion::WaitState1),
+      2
+    );
+    assert_eq!(waitcnt.sequential_access_cycles(GamepakRegion::WaitState1), 1);
+    assert_eq!(waitcnt.nonsequential_access_cycles(GamepakRegion::Sram), 3);
+    let waitcnt = waitcnt.prefetch(true).rom1(Rom1WaitControlCycles::_8, false);
+    assert_eq!(waitcnt.0, 1 << 14 | 3 << 5 | 1);
+  }
+
+  #[test]
+  fn blend_effect_repr_value() {
+    assert_eq!(BlendEffect::AlphaBlend.repr_value(), 1 << 6);
+    assert_eq!(BlendEffect::AlphaBlend.as_index(), Some(1));
+    let mut blend = ColorBlendControlSetting::default();
+    blend.set_blend_effect(BlendEffect::AlphaBlend);
+    assert_eq!(blend.0, BlendEffect::AlphaBlend.repr_value());
+  }
+
+  #[test]
+  fn object_bounding_box() {
+    let mut obj = ObjectAttributes::default();
+    obj.attr0.set_y_coordinate(40);
+    obj.attr0.set_obj_shape(1);
+    obj.attr1.set_x_coordinate(511);
+    obj.attr1.set_obj_size(1);
+    assert_eq!(obj.bounding_box(), (-1, 40, 32, 8));
+    obj.attr0.set_obj_shape(0);
+    obj.attr0.set_y_coordinate(250);
+    obj.attr1.set_x_coordinate(100);
+    obj.attr0.set_obj_display_mode(ObjDisplayMode::DoubleSizeAffine);
+    assert_eq!(obj.bounding_box(), (100, -6, 32, 32));
+    obj.attr0.set_obj_display_mode(ObjDisplayMode::Affine);
+    assert_eq!(obj.bounding_box(), (100, -6, 16, 16));
+  }
+
+  #[test]
+  fn max_objects_per_line_estimate() {
+    let mut display = DisplayControlSetting::default();
+    assert_eq!(display.max_objects_per_line(), 128);
+    display.set_hblank_oam_free(true);
+    assert_eq!(display.max_objects_per_line(), 100);
+  }
+
+  #[test]
+  fn color_approx_eq() {
+    let color = Color::from_channels([10, 20, 30]);
+    assert!(color.approx_eq(color, 0));
+    let near = Color::from_channels([12, 19, 30]);
+    assert!(!color.approx_eq(near, 0));
+    assert!(color.approx_eq(near, 2));
+    assert!(near.approx_eq(color, 2));
+    let far = Color::from_channels([10, 20, 27]);
+    assert!(!color.approx_eq(far, 2));
+  }
+
+  #[test]
+  fn background_vram_layout() {
+    let mut bg = BackgroundControlSetting::default();
+    bg.set_base_screenblock(4);
+    assert_eq!(bg.validate(), Err(BgConfigError::ScreenblockOverlapsTiles));
+    bg.set_base_screenblock(8);
+    assert_eq!(bg.validate(), Ok(()));
+    bg.set_is_8bpp(true);
+    assert_eq!(bg.validate(), Err(BgConfigError::ScreenblockOverlapsTiles));
+    bg.set_base_charblock(2);
+    bg.set_base_screenblock(0);
+    assert_eq!(bg.validate(), Ok(()));
+    bg.set_base_screenblock(30);
+    bg.set_screen_size(3);
+    assert_eq!(bg.validate(), Err(BgConfigError::ScreenblockOutOfBounds));
+  }
+
+  #[test]
+  fn gamepak_region_base_addresses() {
+    assert_eq!(GamepakRegion::WaitState0.base_address(), 0x0800_0000);
+    assert_eq!(GamepakRegion::WaitState1.base_address(), 0x0A00_0000);
+    assert_eq!(GamepakRegion::WaitState2.base_address(), 0x0C00_0000);
+    assert_eq!(GamepakRegion::Sram.base_address(), 0x0E00_0000);
+  }
+
+  #[test]
+  fn dither_gradient_stays_in_bounds() {
+    let from = Color::from_channels([2, 30, 5]);
+    let to = Color::from_channels([9, 20, 5]);
+    let mut out = [Color(0); 24];
+    for phase in [&[][..], &[0, 255], &[32, 96, 160, 224]].iter() {
+      dither_gradient(from, to, &mut out, phase);
+      assert_eq!(out[0], from);
+      assert_eq!(out[23], to);
+      for color in out.iter() {
+        let [r, g, b] = color.channels();
+        assert!((2..=9).contains(&r) && (20..=30).contains(&g) && b == 5);
+      }
+    }
+  }
+
+  #[test]
+  fn blend_active_and_clear() {
+    let mut blend = ColorBlendControlSetting::default();
+    assert!(!blend.is_active());
+    blend.set_first_target_bg1(true);
+    assert!(!blend.is_active());
+    blend.set_blend_effect(BlendEffect::BrightnessDecrease);
+    assert!(blend.is_active());
+    blend.clear();
+    assert!(!blend.is_active());
+    assert_eq!(blend.0, 0);
+  }
+
+  #[test]
+  fn channel_isolation() {
+    let color = Color::from_rgb(10, 20, 30);
+    assert_eq!(color.green_only(), Color::from_rgb(0, 20, 0));
+    assert_eq!(color.red_only(), Color::from_rgb(10, 0, 0));
+    assert_eq!(color.blue_only(), Color::from_rgb(0, 0, 30));
+  }
+
+  #[test]
+  fn display_status_from_ppu_position() {
+    let visible = DisplayStatusSetting::from_ppu_position(10, 100, 50);
+    assert!(!visible.is_vblank() && !visible.is_hblank());
+    assert!(!visible.is_vcount_match());
+    assert_eq!(visible.vcount_setting(), 50);
+    let hblank = DisplayStatusSetting::from_ppu_position(50, 240, 50);
+    assert!(!hblank.is_vblank() && hblank.is_hblank());
+    assert!(hblank.is_vcount_match());
+    let vblank = DisplayStatusSetting::from_ppu_position(160, 0, 0);
+    assert!(vblank.is_vblank() && !vblank.is_hblank());
+    let both = DisplayStatusSetting::from_ppu_position(226, 307, 0);
+    assert!(both.is_vblank() && both.is_hblank());
+    let last_line = DisplayStatusSetting::from_ppu_position(227, 0, 0);
+    assert!(!last_line.is_vblank());
+  }
+
+  #[test]
+  fn filter_sod_left_and_right() {
+    let mut keys = KeyInputLowActive(0b11_1111_1111);
+    keys.set_left_released(false);
+    keys.set_right_released(false);
+    keys.set_up_released(false);
+    keys.set_a_released(false);
+    let filtered = keys.filter_sod();
+    assert!(filtered.left_released() && filtered.right_released());
+    assert!(!filtered.up_released() && !filtered.a_released());
+    let mut keys = KeyInputLowActive(0b11_1111_1111);
+    keys.set_left_released(false);
+    assert_eq!(keys.filter_sod(), keys);
+  }
+
+  #[test]
+  fn palette_checksum_changes() {
+    let mut palette16 = Palette16::from_colors(&[Color::RED, Color::GREEN]);
+    let before = palette16.checksum();
+    assert_eq!(palette16.checksum(), before);
+    palette16.0[15] = Color(1);
+    assert_ne!(palette16.checksum(), before);
+    let mut palette256 = Palette256::from_colors(&[Color::BLUE]);
+    let before = palette256.checksum();
+    palette256.0[200] = Color::RED;
+    assert_ne!(palette256.checksum(), before);
+  }
+
+  #[test]
+  fn narrow_const_enum_in_a_wide_register() {
+    #[allow(dead_code)]
+    mod wide {
+      const_enum! {
+        Narrow(u8 as u16) {
+          Low(0 << 2),
+          High(3 << 2),
+        }
+      }
+      bitstruct_newtype! {
+        WideRegister(u16) {
+          [2-3 => Narrow: narrow, set_narrow],
+          [8-15: upper, set_upper],
+        }
+      }
+    }
+    use wide::{Narrow, WideRegister};
+    let high: u8 = Narrow::High.repr_value();
+    assert_eq!(high, 0b1100);
+    let widened: u16 = Narrow::High.widen();
+    assert_eq!(widened, 0b1100);
+    let mut value = WideRegister::from_bits(0xAB00);
+    assert_eq!(value.narrow(), Narrow::Low);
+    value.set_narrow(Narrow::High);
+    assert_eq!(value.narrow(), Narrow::High);
+    assert_eq!(value.upper(), 0xAB);
+    assert_eq!(value.to_bits(), 0xAB00 | Narrow::High.widen());
+  }
+
+  #[test]
+  fn double_size_y_range_wraps() {
+    let mut attr0 = ObjAttr0::default();
+    attr0.set_y_coordinate(240);
+    assert_eq!(attr0.effective_y_range(16), (240, 255));
+    attr0.set_obj_display_mode(ObjDisplayMode::DoubleSizeAffine);
+    assert_eq!(attr0.effective_y_range(16), (240, 15));
+    attr0.set_y_coordinate(255);
+    assert_eq!(attr0.effective_y_range(64), (255, 126));
+    attr0.set_y_coordinate(100);
+    assert_eq!(attr0.effective_y_range(8), (100, 115));
+  }
+
+  #[test]
+  fn bitmap_rows() {
+    let mut mode3 = Mode3Bitmap::default();
+    assert!(mode3.row_mut(160).is_none());
+    let row = mode3.row_mut(159).unwrap();
+    assert_eq!(row.len(), 240);
+    row.fill(Color::RED);
+    assert_eq!(mode3.0[159 * 240], Color::RED);
+    assert_eq!(mode3.0[159 * 240 - 1], Color(0));
+    let mut mode4 = Mode4Bitmap::default();
+    assert!(mode4.row_mut(usize::MAX).is_none());
+    mode4.row_mut(1).unwrap().fill(7);
+    assert_eq!(mode4.0[239], 0);
+    assert!(mode4.0[240..480].iter().all(|&i| i == 7));
+    assert_eq!(mode4.0[480], 0);
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn changed_display_fields() {
+    let old = DisplayControlSetting::sprites_mode0();
+    let mut new = old;
+    new.set_video_mode(VideoMode::_3);
+    new.set_display_bg2(true);
+    assert_eq!(changed_bits(old, new), 0b0100_0000_0011);
+    let names: alloc::vec::Vec<_> =
+      changed_fields(old, new).iter().map(|f| f.name).collect();
+    assert_eq!(names, ["video_mode", "display_bg2"]);
+    new.set_obj_vram_is_1d(false);
+    let names: alloc::vec::Vec<_> =
+      changed_fields(old, new).iter().map(|f| f.name).collect();
+    assert_eq!(names, ["video_mode", "obj_vram_is_1d", "display_bg2"]);
+    assert!(changed_fields(old, old).is_empty());
+  }
+
+  #[test]
+  fn declaration_index_lookup() {
+    use core::convert::TryFrom;
+    assert_eq!(VideoMode::try_from(DeclarationIndex(0)), Ok(VideoMode::_0));
+    assert_eq!(
+      BlendEffect::try_from(DeclarationIndex(1)),
+      Ok(BlendEffect::AlphaBlend)
+    );
+    assert_eq!(
+      VideoMode::try_from(DeclarationIndex(6)),
+      Err(DeclarationIndex(6))
+    );
+    assert_eq!(BlendEffect::from_index(usize::MAX), None);
+  }
+
+  #[test]
+  fn premultiply_coefficients() {
+    let color = Color::from_channels([31, 10, 1]);
+    assert_eq!(color.premultiply(0), [0, 0, 0]);
+    assert_eq!(color.premultiply(8), [248, 80, 8]);
+    assert_eq!(color.premultiply(16), [496, 160, 16]);
+    assert_eq!(color.premultiply(200), color.premultiply(16));
+  }
+
+  #[test]
+  fn screen_entry_descriptor_round_trip() {
+    let descriptor = ScreenEntryDescriptor {
+      tile_id: 513,
+      h_flip: true,
+      v_flip: false,
+      palbank: 9,
+    };
+    let entry = TextScreenEntry::from(descriptor);
+    assert_eq!(entry.0, 9 << 12 | 1 << 10 | 513);
+    assert_eq!(ScreenEntryDescriptor::from(entry), descriptor);
+    let entry = TextScreenEntry(0xFFFF);
+    assert_eq!(
+      TextScreenEntry::from(ScreenEntryDescriptor::from(entry)),
+      entry
+    );
+  }
+
+  #[test]
+  fn available_bg_count_per_mode() {
+    let counts = [
+      (VideoMode::_0, 4),
+      (VideoMode::_1, 3),
+      (VideoMode::_2, 2),
+      (VideoMode::_3, 1),
+      (VideoMode::_4, 1),
+      (VideoMode::_5, 1),
+    ];
+    for &(mode, count) in counts.iter() {
+      let mut display = DisplayControlSetting::default();
+      display.set_video_mode(mode);
+      assert_eq!(display.available_bg_count(), count, "{:?}", mode);
+    }
+  }
+
+  #[test]
+  fn palette_le_bytes_ends() {
+    let mut palette = Palette256::from_colors(&[Color(0x1234)]);
+    palette.0[255] = Color(0x7C1F);
+    assert_eq!(palette.le_bytes().count(), 512);
+    let mut bytes = palette.le_bytes();
+    assert_eq!(
+      (bytes.next(), bytes.next(), bytes.next()),
+      (Some(0x34), Some(0x12), Some(0))
+    );
+    let mut last = palette.le_bytes().skip(510);
+    assert_eq!(
+      (last.next(), last.next(), last.next()),
+      (Some(0x1F), Some(0x7C), None)
+    );
+  }
+
+  #[test]
+  fn half_speed_parallax() {
+    let layer = BackgroundScroll::parallax(100, 41, 1, 2);
+    assert_eq!((layer.h.offset(), layer.v.offset()), (50, 20));
+    let layer = BackgroundScroll::parallax(1100, 0, 1, 2);
+    assert_eq!(layer.h.offset(), 550 % 512);
+    let full = BackgroundScroll::parallax(300, 600, 1, 1);
+    assert_eq!(full, BackgroundScroll::from_pixels(300, 600));
+  }
+
+  #[test]
+  fn sepia_tint_of_gray() {
+    let gray = Color::from_rgb(16, 16, 16);
+    let sepia = Color::from_rgb(31, 24, 15);
+    assert_eq!(gray.tint(sepia, 16), Color::from_rgb(16, 12, 7));
+    assert_eq!(gray.tint(sepia, 100), gray.tint(sepia, 16));
+    assert_eq!(gray.tint(sepia, 8), Color::from_rgb(16, 14, 11));
+    assert_eq!(gray.tint(sepia, 0), gray);
+  }
+
+  #[test]
+  fn sprites_mode0_bits() {
+    let display = DisplayControlSetting::sprites_mode0();
+    assert_eq!(display.0, 1 << 12 | 1 << 6);
+    assert_eq!(display.video_mode(), VideoMode::_0);
+    assert!(display.display_obj() && display.obj_vram_is_1d());
+    assert!(!display.forced_blank());
+    assert_eq!(display.active_backgrounds(), [false; 4]);
+    assert_eq!(display.validate(), Ok(()));
+  }
+
+  #[test]
+  fn zero_saturation_is_gray() {
+    let color = Color::from_rgb(31, 0, 0);
+    assert_eq!(color.luminance(), 9);
+    assert_eq!(color.adjust_saturation(0, 1), Color::from_rgb(9, 9, 9));
+    assert_eq!(color.adjust_saturation(1, 1), color);
+    let gray = Color::from_rgb(20, 20, 20);
+    assert_eq!(gray.adjust_saturation(0, 1), gray);
+    assert_eq!(
+      Color::from_rgb(31, 10, 0).adjust_saturation(2, 1).channels()[0],
+      31
+    );
+  }
+
+  #[test]
+  fn contrast_up_and_down() {
+    let color = Color::from_rgb(24, 8, 16);
+    assert_eq!(color.adjust_contrast(2, 1), Color::from_rgb(31, 0, 16));
+    assert_eq!(color.adjust_contrast(1, 2), Color::from_rgb(20, 12, 16));
+    assert_eq!(color.adjust_contrast(0, 1), Color::from_rgb(16, 16, 16));
+    assert_eq!(color.adjust_contrast(3, 3), color);
+  }
+
+  #[test]
+  fn palette_gradient_fill() {
+    let mut palette = Palette16::default();
+    let to = Color::from_rgb(30, 15, 0);
+    palette.fill_gradient(0, 15, Color::BLACK, to);
+    assert_eq!(palette.0[0], Color::BLACK);
+    assert_eq!(palette.0[15], to);
+    for (i, color) in palette.0.iter().enumerate() {
+      assert_eq!(color.channels(), [2 * i as u8, i as u8, 0]);
+    }
+    let mut palette = Palette256::default();
+    palette.fill_gradient(10, 10, Color::RED, Color::BLUE);
+    assert_eq!(palette.0[10], Color::RED);
+    assert_eq!(palette.0[9], Color(0));
+    assert_eq!(palette.0[11], Color(0));
+  }
+
+  #[test]
+  #[should_panic(expected = "gradient end is before its start")]
+  fn palette_gradient_backwards() {
+    let mut palette = Palette16::default();
+    palette.fill_gradient(5, 4, Color::RED, Color::BLUE);
+  }
+
+  #[test]
+  fn reference_point_negative_values() {
+    let min = BackgroundReferencePoint::from_bits(0x0800_0000);
+    assert_eq!(min.to_bits(), -0x0800_0000);
+    assert_eq!((min.whole(), min.fraction()), (-0x8_0000, 0));
+    let max = BackgroundReferencePoint::from_bits(0x07FF_FFFF);
+    assert_eq!((max.whole(), max.fraction()), (0x7_FFFF, 0xFF));
+    let minus_half = BackgroundReferencePoint::from_bits(-0x80);
+    assert_eq!((minus_half.whole(), minus_half.fraction()), (-1, 0x80));
+    let ignored_top =
+      BackgroundReferencePoint::from_bits(0x0FFF_FF80_u32 as i32);
+    assert_eq!(ignored_top, minus_half);
+    let wrapped = BackgroundReferencePoint::from_bits(0xF000_0100_u32 as i32);
+    assert_eq!(wrapped.whole(), 1);
+  }
+
+  #[test]
+  fn bitmap_mode_membership() {
+    const BITMAP: &[VideoMode] = &[VideoMode::_3, VideoMode::_4, VideoMode::_5];
+    for &mode in VideoMode::ALL {
+      assert_eq!(mode.matches_any(BITMAP), mode.is_bitmap(), "{:?}", mode);
+    }
+    assert!(!VideoMode::_3.matches_any(&[]));
+  }
+
+  #[test]
+  fn bind_affine_slot() {
+    let mut obj = ObjectAttributes::default();
+    obj.attr1.set_horizontal_flip(true);
+    let matrix = obj.bind_affine(5, AffineMatrix::IDENTITY);
+    assert_eq!(matrix, AffineMatrix::IDENTITY);
+    assert_eq!(obj.attr0.obj_display_mode(), ObjDisplayMode::Affine);
+    assert_eq!(obj.attr1.affine_param(), 5);
+    assert_eq!(obj_affine_offsets(5), [166, 174, 182, 190]);
+    obj.attr0.set_obj_display_mode(ObjDisplayMode::DoubleSizeAffine);
+    obj.bind_affine(33, matrix);
+    assert_eq!(obj.attr0.obj_display_mode(), ObjDisplayMode::DoubleSizeAffine);
+    assert_eq!(obj.attr1.affine_param(), 1);
+  }
+
+  #[test]
+  fn fast_gamepak_differs_from_bios() {
+    assert!(!WaitControlSetting::hardware_reset().differs_from_bios_default());
+    let fast = WaitControlSetting::fast_gamepak();
+    assert_eq!(fast.0, 0x4317);
+    assert!(fast.differs_from_bios_default());
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn fast_gamepak_diff_from_bios() {
+    let names: alloc::vec::Vec<_> = WaitControlSetting::fast_gamepak()
+      .diff_from_bios_default()
+      .iter()
+      .map(|f| f.name)
+      .collect();
+    assert_eq!(
+      names,
+      [
+        "sram_wait",
+        "wait0_first_access",
+        "wait0_second_access_1cycle",
+        "wait2_first_access",
+        "game_pak_prefetch_enabled"
+      ]
+    );
+    assert!(WaitControlSetting::hardware_reset()
+      .diff_from_bios_default()
+      .is_empty());
+  }
+
+  #[test]
+  fn rgb8_round_trips() {
+    assert_eq!(Color::WHITE.to_rgb8(), [255, 255, 255]);
+    assert_eq!(Color::from_rgb(16, 1, 0).to_rgb8(), [132, 8, 0]);
+    for raw in 0..0x8000 {
+      let color = Color(raw);
+      assert_eq!(Color::from_rgb8(color.to_rgb8()), color);
+    }
+    assert_eq!(Color::from_rgb8([128, 4, 3]), Color::from_rgb(16, 0, 0));
+  }
+
+  #[test]
+  fn blend_preview_over_backdrop() {
+    let mut blend = ColorBlendControlSetting::default();
+    blend.set_first_target(BlendLayer::Bg0, true);
+    blend.set_second_target(BlendLayer::Backdrop, true);
+    blend.set_blend_effect(BlendEffect::AlphaBlend);
+    let mut alpha = AlphaBlendCoefficientSetting::default();
+    alpha.set_eva_coefficient(8);
+    alpha.set_evb_coefficient(8);
+    let brightness = BrightnessCoefficientSetting::default();
+    let top = (BlendLayer::Bg0, Color::RED);
+    let mixed =
+      blend.preview_over_backdrop(top, Color::BLUE, alpha, brightness);
+    assert_eq!(mixed, Color::from_rgb(15, 0, 15));
+    let bg1 = (BlendLayer::Bg1, Color::RED);
+    assert_eq!(
+      blend.preview_over_backdrop(bg1, Color::BLUE, alpha, brightness),
+      Color::RED
+    );
+    blend.set_second_target(BlendLayer::Backdrop, false);
+    assert_eq!(
+      blend.preview_over_backdrop(top, Color::BLUE, alpha, brightness),
+      Color::RED
+    );
+  }
+
+  #[test]
+  fn blend_preview_full_brightness() {
+    let mut blend = ColorBlendControlSetting::default();
+    blend.set_first_target(BlendLayer::Obj, true);
+    let alpha = AlphaBlendCoefficientSetting::default();
+    let mut brightness = BrightnessCoefficientSetting::default();
+    brightness.set_coefficient(16);
+    let top = (BlendLayer::Obj, Color::from_rgb(10, 20, 31));
+    let below = (BlendLayer::Bg0, Color::BLACK);
+    blend.set_blend_effect(BlendEffect::BrightnessIncrease);
+    assert_eq!(blend.preview(top, below, alpha, brightness), Color::WHITE);
+    blend.set_blend_effect(BlendEffect::BrightnessDecrease);
+    assert_eq!(blend.preview(top, below, alpha, brightness), Color::BLACK);
+    brightness.set_coefficient(31);
+    assert_eq!(brightness.effective_coefficient(), 16);
+    assert_eq!(blend.preview(top, below, alpha, brightness), Color::BLACK);
+    brightness.set_coefficient(8);
+    blend.set_blend_effect(BlendEffect::BrightnessIncrease);
+    assert_eq!(
+      blend.preview(top, below, alpha, brightness),
+      Color::from_rgb(20, 25, 31)
+    );
+  }
+
+  #[test]
+  fn video_mode_iter_from() {
+    let mut modes = VideoMode::_4.iter_from();
+    for &expected in [
+      VideoMode::_4,
+      VideoMode::_5,
+      VideoMode::_0,
+      VideoMode::_1,
+      VideoMode::_2,
+      VideoMode::_3,
+    ]
+    .iter()
+    {
+      assert_eq!(modes.next(), Some(expected));
+    }
+    assert_eq!(modes.next(), None);
+    assert_eq!(VideoMode::_0.iter_from().count(), 6);
+    assert!(VideoMode::_0.iter_from().eq(VideoMode::ALL.iter().copied()));
+  }
+
+  #[test]
+  fn display_validate_invalid_configs() {
+    assert_eq!(DisplayControlSetting::sprites_mode0().validate(), Ok(()));
+    assert_eq!(
+      DisplayControlSetting(7).validate(),
+      Err(DisplayConfigError::InvalidVideoMode)
+    );
+    let mut display = DisplayControlSetting::default();
+    display.set_video_mode(VideoMode::_3);
+    display.set_display_bg0(true);
+    assert_eq!(
+      display.validate(),
+      Err(DisplayConfigError::BackgroundNotInMode(0))
+    );
+    display.set_video_mode(VideoMode::_2);
+    display.set_display_bg0(false);
+    display.set_display_bg3(true);
+    assert_eq!(display.validate(), Ok(()));
+    display.set_display_obj_win(true);
+    assert_eq!(
+      display.validate(),
+      Err(DisplayConfigError::ObjWindowWithoutObjects)
+    );
+    display.set_display_obj_win(false);
+    display.set_forced_blank(true);
+    assert_eq!(
+      display.validate(),
+      Err(DisplayConfigError::ForcedBlankWithLayers)
+    );
+    display.set_display_bg3(false);
+    assert_eq!(display.validate(), Ok(()));
+  }
+
+  #[test]
+  fn checked_raw_value_round_trip() {
+    use core::convert::TryFrom;
+    for &mode in VideoMode::ALL {
+      assert_eq!(VideoMode::from_bits_checked(mode.to_bits()), Some(mode));
+      assert_eq!(VideoMode::try_from(mode.to_bits()), Ok(mode));
+    }
+    assert_eq!(VideoMode::from_bits_checked(6), None);
+    assert_eq!(VideoMode::try_from(7), Err(7));
+    let alpha = BlendEffect::AlphaBlend.to_bits();
+    assert_eq!(BlendEffect::try_from(alpha), Ok(BlendEffect::AlphaBlend));
+    assert_eq!(BlendEffect::try_from(1), Err(1));
+    assert_eq!(
+      unsafe { BlendEffect::from_bits(alpha) },
+      BlendEffect::AlphaBlend
+    );
+  }
+
+  #[test]
+  fn interrupt_master_enable_constants() {
+    assert!(InterruptMasterEnable::ENABLED.enabled());
+    assert_eq!(InterruptMasterEnable::ENABLED.to_bits(), 1);
+    assert!(!InterruptMasterEnable::DISABLED.enabled());
+    assert_eq!(InterruptMasterEnable::DISABLED.to_bits(), 0);
+    let mut ime = InterruptMasterEnable::DISABLED;
+    ime.toggle_enabled();
+    assert_eq!(ime, InterruptMasterEnable::ENABLED);
   }
 }
@@ -0,0 +1,383 @@
+//! Serial communication registers, used with the link cable.
+//!
+//! The serial control register (`SIOCNT`) is interpreted differently depending
+//! on which communication mode is active, so there's a separate type for each
+//! mode's view of that register.
+//!
+//! Register offsets given in this module are byte offsets from the start of
+//! the IO register region, 0x04000000.
+
+const_enum! {
+  /// The baud rate of a multiplayer or UART mode transfer.
+  SioBaudRate(u16) {
+    /// 9600 bits per second.
+    _9600(0),
+    /// 38400 bits per second.
+    _38400(1),
+    /// 57600 bits per second.
+    _57600(2),
+    /// 115200 bits per second.
+    _115200(3),
+  }
+}
+
+/// The baud rate field of [`SerialControlSetting`].
+///
+/// This is the same type as [`SioBaudRate`], which the multiplayer mode view
+/// of the register also uses.
+pub type SerialBaudRate = SioBaudRate;
+
+/// The slow internal shift clock rate of normal mode, in Hz.
+const CLOCK_256KHZ: u32 = 16_777_216 / 64;
+
+/// The fast internal shift clock rate of normal mode, in Hz.
+const CLOCK_2MHZ: u32 = 16_777_216 / 8;
+
+/// How far a baud rate is from a number of bits per second, squared.
+const fn bps_distance(rate: SioBaudRate, bps: u32) -> i64 {
+  let diff = rate.bits_per_second() as i64 - bps as i64;
+  diff * diff
+}
+
+impl SioBaudRate {
+  /// The rate in bits per second.
+  #[inline]
+  #[must_use]
+  pub const fn bits_per_second(self) -> u32 {
+    match self {
+      SioBaudRate::_9600 => 9600,
+      SioBaudRate::_38400 => 38400,
+      SioBaudRate::_57600 => 57600,
+      _ => 115200,
+    }
+  }
+
+  /// The supported rate closest to `bps`. A tie goes to the slower rate.
+  #[inline]
+  #[must_use]
+  pub const fn from_bps(bps: u32) -> Self {
+    let mut best = Self::ALL[0];
+    let mut i = 1;
+    while i < Self::ALL.len() {
+      let rate = Self::ALL[i];
+      if bps_distance(rate, bps) < bps_distance(best, bps) {
+        best = rate;
+      }
+      i += 1;
+    }
+    best
+  }
+}
+
+bitstruct_newtype! {
+  /// The serial control register (`SIOCNT`) while in multiplayer mode.
+  ///
+  /// In multiplayer mode up to four GBAs are linked together. The parent
+  /// starts each transfer, then every GBA sends one `u16` from `SIOMLT_SEND`
+  /// and receives all four players' values in the `SIOMULTI` registers.
+  SioControlMultiplayer(u16) {
+    /// The baud rate of the transfer.
+    [0-1 => SioBaudRate: baud_rate, set_baud_rate],
+    /// Read only. Clear if this GBA is the parent, set if it's a child.
+    [2: is_child, set_is_child, toggle_is_child],
+    /// Read only. Set once all the connected GBAs are ready.
+    [3: all_ready, set_all_ready, toggle_all_ready],
+    /// Read only. The ID of this GBA, 0 for the parent and 1-3 for the
+    /// children.
+    [4-5: multiplayer_id, set_multiplayer_id],
+    /// Read only. Set if the last transfer had an error.
+    [6: error, set_error, toggle_error],
+    /// Set to start a transfer. Stays set while the transfer is busy. Only the
+    /// parent can start a transfer.
+    [7: busy, set_busy, toggle_busy],
+    /// Set to generate an interrupt when a transfer completes.
+    [14: irq_enabled, set_irq_enabled, toggle_irq_enabled],
+  }
+}
+
+impl SioControlMultiplayer {
+  /// If a transfer is running. This is the `busy` bit.
+  #[inline]
+  #[must_use]
+  pub const fn transfer_in_progress(self) -> bool {
+    self.busy()
+  }
+
+  /// This value with the start bit set, to begin a transfer.
+  #[inline]
+  #[must_use]
+  pub const fn start_transfer(self) -> Self {
+    let mut control = self;
+    control.set_busy(true);
+    control
+  }
+
+  /// This GBA's player index, 0 through 3.
+  ///
+  /// This is also the `SIOMULTI` slot that holds the data this GBA sent.
+  #[inline]
+  #[must_use]
+  pub const fn player_index(self) -> usize {
+    self.multiplayer_id() as usize
+  }
+
+  /// The register offset of the `SIOMULTI` slot holding a player's data.
+  ///
+  /// Returns `None` if the player index is more than 3.
+  #[inline]
+  #[must_use]
+  pub const fn data_slot_offset(player: usize) -> Option<usize> {
+    if player < 4 {
+      Some(0x120 + player * 2)
+    } else {
+      None
+    }
+  }
+}
+
+bitstruct_newtype! {
+  /// The serial control register (`SIOCNT`) while in normal mode.
+  ///
+  /// Normal mode is a plain shift register link between two GBAs (or a GBA
+  /// and another device). The side using the internal clock drives the
+  /// transfer, and both sides exchange 8 or 32 bits at once through
+  /// `SIODATA8` or `SIODATA32`.
+  SioControlNormal(u16) {
+    /// Set to use the internal shift clock, clear to use the external clock
+    /// from the other device.
+    [0: internal_clock, set_internal_clock, toggle_internal_clock],
+    /// The internal shift clock speed. Clear for 256KHz, set for 2MHz.
+    [1: clock_2mhz, set_clock_2mhz, toggle_clock_2mhz],
+    /// Read only. The state of the SI line, which is the other device's SO.
+    [2: si_state, set_si_state, toggle_si_state],
+    /// The state of the SO line while no transfer is running.
+    [3: so_during_inactivity, set_so_during_inactivity, toggle_so_during_inactivity],
+    /// Set to start a transfer. Stays set while the transfer is busy.
+    [7: busy, set_busy, toggle_busy],
+    /// The transfer length. Clear for 8 bits, set for 32 bits.
+    [12: transfer32, set_transfer32, toggle_transfer32],
+    /// Set to generate an interrupt when a transfer completes.
+    [14: irq_enabled, set_irq_enabled, toggle_irq_enabled],
+  }
+}
+
+impl SioControlNormal {
+  /// If a transfer is running. This is the `busy` bit.
+  #[inline]
+  #[must_use]
+  pub const fn transfer_in_progress(self) -> bool {
+    self.busy()
+  }
+
+  /// This value with the start bit set, to begin a transfer.
+  #[inline]
+  #[must_use]
+  pub const fn start_transfer(self) -> Self {
+    let mut control = self;
+    control.set_busy(true);
+    control
+  }
+
+  /// The internal shift clock rate in Hz, picked by `clock_2mhz`.
+  ///
+  /// These are the exact rates, the CPU clock divided by 64 or by 8, rather
+  /// than the rounded 256KHz and 2MHz they're usually called.
+  #[inline]
+  #[must_use]
+  pub const fn internal_clock_hz(self) -> u32 {
+    if self.clock_2mhz() {
+      CLOCK_2MHZ
+    } else {
+      CLOCK_256KHZ
+    }
+  }
+
+  /// Sets `clock_2mhz` to whichever internal shift clock rate is closest to
+  /// `hz`. A tie goes to the slower rate.
+  #[inline]
+  pub const fn set_internal_clock_hz(&mut self, hz: u32) {
+    self.set_clock_2mhz(hz > CLOCK_256KHZ + (CLOCK_2MHZ - CLOCK_256KHZ) / 2);
+  }
+}
+
+bitstruct_newtype! {
+  /// The serial control register (`SIOCNT`) with just the fields that are
+  /// common to the normal and multiplayer modes.
+  ///
+  /// The meaning of some bits depends on the mode, as noted on each field.
+  /// [`SioControlNormal`] and [`SioControlMultiplayer`] give the full view of
+  /// the register in those modes. The baud rate uses [`SerialBaudRate`],
+  /// which is the same type as [`SioBaudRate`].
+  ///
+  /// Bits that are read only in some modes still have setters, since the
+  /// same field is writable in other modes. In a read only mode the setter
+  /// only changes this value, and the hardware ignores the bit when it's
+  /// written.
+  SerialControlSetting(u16) {
+    /// Multiplayer and UART mode: the baud rate of the transfer. In normal
+    /// mode these bits pick the shift clock source and speed instead.
+    [0-1 => SioBaudRate: baud_rate, set_baud_rate],
+    /// Read only in every mode. Normal mode: the state of the SI line.
+    /// Multiplayer mode: set if this GBA is a child.
+    [2: si_state, set_si_state, toggle_si_state],
+    /// Normal mode: the state of the SO line while no transfer is running.
+    /// Multiplayer mode: read only, set once all GBAs are ready.
+    [3: so_state, set_so_state, toggle_so_state],
+    /// Normal and multiplayer mode: set to start a transfer. Stays set while
+    /// the transfer is busy.
+    [7: busy, set_busy, toggle_busy],
+    /// Normal mode only: the transfer length. Clear for 8 bits, set for 32
+    /// bits. In multiplayer mode transfers are always 16 bits.
+    ///
+    /// This is also the low bit of the mode select in bits 12-13 (see
+    /// [`SioMode::Serial`]). It must be clear in multiplayer mode, since with
+    /// bit 13 set it picks UART mode instead.
+    [12: transfer32, set_transfer32, toggle_transfer32],
+    /// All modes: set to generate an interrupt when a transfer completes.
+    [14: irq_enabled, set_irq_enabled, toggle_irq_enabled],
+  }
+}
+
+const_enum! {
+  /// The communication mode selected by `RCNT`.
+  SioMode(u16) {
+    /// One of the serial modes. Which one is then picked by bits 12-13 of
+    /// `SIOCNT`, which is interpreted according to that mode:
+    ///
+    /// * Bit 13 clear: normal mode, with bit 12 picking 8-bit or 32-bit
+    ///   transfers.
+    /// * Bit 13 set and bit 12 clear: multiplayer mode.
+    /// * Bits 12 and 13 both set: UART mode.
+    Serial(0 << 14),
+    /// The same as `Serial`. Bit 14 doesn't matter while bit 15 is clear, so
+    /// hardware can read back either value. Use
+    /// [`is_serial`](SioMode::is_serial) to check for both.
+    SerialBit14(1 << 14),
+    /// General purpose mode: the four link port pins are controlled directly
+    /// through `RCNT`, and `SIOCNT` isn't used.
+    GeneralPurpose(2 << 14),
+    /// JoyBus mode, used to talk to a GameCube.
+    JoyBus(3 << 14),
+  }
+}
+
+impl SioMode {
+  /// If this is one of the serial modes, `Serial` or `SerialBit14`.
+  #[inline]
+  #[must_use]
+  pub const fn is_serial(self) -> bool {
+    matches!(self, SioMode::Serial | SioMode::SerialBit14)
+  }
+}
+
+bitstruct_newtype! {
+  /// The mode select and general purpose IO register (`RCNT`).
+  ///
+  /// Bits 14-15 pick the communication mode, which changes how `SIOCNT` is
+  /// interpreted. With bit 15 clear it's one of the serial modes and bit 14
+  /// doesn't matter. The other fields only apply in general purpose mode.
+  RawCommunicationSetting(u16) {
+    /// The data of the SC, SD, SI, and SO pins, in bits 0 through 3. For
+    /// output pins this is written, for input pins it's read.
+    [0-3: gpio_data, set_gpio_data],
+    /// The direction of each pin, in the same order as `gpio_data`. A set bit
+    /// makes that pin an output.
+    [4-7: gpio_direction, set_gpio_direction],
+    /// Set to generate an interrupt when the SI pin goes from high to low.
+    [8: si_irq_enabled, set_si_irq_enabled, toggle_si_irq_enabled],
+    /// The communication mode.
+    [14-15 => SioMode: sio_mode, set_sio_mode],
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn multiplayer_data_slots() {
+    let mut control = SioControlMultiplayer::default();
+    control.set_multiplayer_id(2);
+    assert_eq!(control.player_index(), 2);
+    assert_eq!(SioControlMultiplayer::data_slot_offset(0), Some(0x120));
+    assert_eq!(SioControlMultiplayer::data_slot_offset(1), Some(0x122));
+    assert_eq!(SioControlMultiplayer::data_slot_offset(2), Some(0x124));
+    assert_eq!(SioControlMultiplayer::data_slot_offset(3), Some(0x126));
+    assert_eq!(SioControlMultiplayer::data_slot_offset(4), None);
+  }
+
+  #[test]
+  fn busy_flag_on_both_modes() {
+    let multi = SioControlMultiplayer::default();
+    assert!(!multi.transfer_in_progress());
+    let multi = multi.start_transfer();
+    assert!(multi.transfer_in_progress());
+    assert_eq!(multi.0, 1 << 7);
+    let mut normal = SioControlNormal::default();
+    normal.set_transfer32(true);
+    assert!(!normal.transfer_in_progress());
+    let normal = normal.start_transfer();
+    assert!(normal.transfer_in_progress());
+    assert_eq!(normal.0, 1 << 12 | 1 << 7);
+  }
+
+  #[test]
+  fn baud_rates() {
+    let rates = [
+      (SioBaudRate::_9600, 9600),
+      (SioBaudRate::_38400, 38400),
+      (SioBaudRate::_57600, 57600),
+      (SioBaudRate::_115200, 115200),
+    ];
+    for &(rate, bps) in rates.iter() {
+      assert_eq!(rate.bits_per_second(), bps);
+      assert_eq!(SioBaudRate::from_bps(bps), rate);
+    }
+    assert_eq!(SioBaudRate::from_bps(0), SioBaudRate::_9600);
+    assert_eq!(SioBaudRate::from_bps(48000), SioBaudRate::_38400);
+    assert_eq!(SioBaudRate::from_bps(1_000_000), SioBaudRate::_115200);
+  }
+
+  #[test]
+  fn sio_mode_bit_14() {
+    let rcnt = RawCommunicationSetting(0b01 << 14);
+    assert_eq!(rcnt.sio_mode(), SioMode::SerialBit14);
+    assert!(rcnt.sio_mode().as_index().is_some());
+    assert!(rcnt.sio_mode().is_serial());
+    assert!(SioMode::Serial.is_serial());
+    assert!(!SioMode::GeneralPurpose.is_serial());
+    assert!(!SioMode::JoyBus.is_serial());
+  }
+
+  #[test]
+  fn normal_mode_clocks() {
+    let mut control = SioControlNormal::default();
+    assert_eq!(control.internal_clock_hz(), 262_144);
+    control.set_clock_2mhz(true);
+    assert_eq!(control.internal_clock_hz(), 2_097_152);
+    control.set_internal_clock_hz(256_000);
+    assert!(!control.clock_2mhz());
+    control.set_internal_clock_hz(2_000_000);
+    assert!(control.clock_2mhz());
+  }
+
+  #[test]
+  fn serial_control_field_layout() {
+    let mut control = SerialControlSetting::default();
+    control.set_baud_rate(SerialBaudRate::_115200);
+    assert_eq!(control.0, 0b11);
+    control.set_busy(true);
+    assert_eq!(control.0, 1 << 7 | 0b11);
+    control.set_transfer32(true);
+    assert_eq!(control.0, 1 << 12 | 1 << 7 | 0b11);
+    control.set_irq_enabled(true);
+    assert_eq!(control.0, 1 << 14 | 1 << 12 | 1 << 7 | 0b11);
+    control.set_si_state(true);
+    control.set_so_state(true);
+    assert_eq!(control.0 & 0b1100, 0b1100);
+    let control = SerialControlSetting(1 << 14 | 1 << 12 | 1 << 7 | 0b01);
+    assert_eq!(control.baud_rate(), SioBaudRate::_38400);
+    assert!(control.busy() && control.transfer32() && control.irq_enabled());
+    assert!(!control.si_state() && !control.so_state());
+  }
+}
@@ -34,7 +34,7 @@ bitstruct_newtype! {
     /// See decreasing_sweep for that.
     [0-2: sweep_shift_count, set_sweep_shift_count],
     /// If set to false, the tone will increase. If set to true, the tone will decrease.
-    [3: decreasing_sweep, set_decreasing_sweep],
+    [3: decreasing_sweep, set_decreasing_sweep, toggle_decreasing_sweep],
     /// The amount of time between each step the shift takes, in units of 7.8ms.
     [4-6: sweep_time_chunk, set_sweep_time_chunk],
   }
@@ -54,6 +54,16 @@ const_enum! {
   }
 }
 
+const_enum! {
+  /// Which way an envelope moves the volume.
+  EnvelopeDirection(u16) {
+    /// The volume steps down towards 0, which is silent.
+    Decrease(0 << 11),
+    /// The volume steps up towards 15. It never reaches silence this way.
+    Increase(1 << 11),
+  }
+}
+
 bitstruct_newtype! {
   /// Set the duty cycle, length, and envelope of a tone generated by channel 1 or 2.
   ToneDutyLenEnvelope(u16) {
@@ -65,12 +75,49 @@ bitstruct_newtype! {
       /// Use the increasing_envelope field to set if this value is incrementing or decrementing.
       [8-10: envelope_step_time, set_envelope_step_time],
       /// Set to true for an increasing envelope. Set to false for a decreasing envelope.
-      [11: increasing_envelope, set_increasing_envelope],
+      [11: increasing_envelope, set_increasing_envelope, toggle_increasing_envelope],
       /// Set the initial volume of the tone.
       [12-15: initial_volume, set_initial_volume],
   }
 }
 
+impl ToneDutyLenEnvelope {
+  /// The same bit as `increasing_envelope`, as an [`EnvelopeDirection`].
+  #[inline]
+  #[must_use]
+  pub const fn envelope_direction(self) -> EnvelopeDirection {
+    if self.increasing_envelope() {
+      EnvelopeDirection::Increase
+    } else {
+      EnvelopeDirection::Decrease
+    }
+  }
+
+  /// Sets the `increasing_envelope` bit from an [`EnvelopeDirection`].
+  #[inline]
+  pub const fn set_envelope_direction(&mut self, direction: EnvelopeDirection) {
+    self.set_increasing_envelope(direction.0 != 0);
+  }
+
+  /// The volume the tone ends up at once the envelope has finished.
+  ///
+  /// With a step time of 0 there's no envelope, so the volume stays at the
+  /// initial volume. Otherwise it steps all the way to 15 or to 0 depending on
+  /// the direction.
+  #[inline]
+  #[must_use]
+  pub const fn final_volume(self) -> u16 {
+    if self.envelope_step_time() == 0 {
+      self.initial_volume()
+    } else {
+      match self.envelope_direction() {
+        EnvelopeDirection::Increase => 15,
+        _ => 0,
+      }
+    }
+  }
+}
+
 bitstruct_newtype! {
   /// Control register used for tones generated by audio channel 1 and 2.
   ToneFrequencyControl(u16) {
@@ -78,10 +125,96 @@ bitstruct_newtype! {
     [0-10: frequency, set_frequency],
     /// Set to true to cause the tone to stop as soon as its time expires.
     /// Setting to false will cause the tone to restart as soon as it finishes, making for a continuous tone.
-    [14: stop_at_end, set_stop_at_end],
+    [14: stop_at_end, set_stop_at_end, toggle_stop_at_end],
     /// Set to true to start the tone.
     /// If set to false while the tone is playing, the tone will stop.
-    [15: init, set_init],
+    [15: init, set_init, toggle_init],
+  }
+}
+
+/// The common controls of the two square wave channels, 1 and 2.
+///
+/// These only change the register values, which then still need to be
+/// written to the channel's registers.
+pub trait SquareChannel {
+  /// The channel's duty, length, and envelope register value.
+  fn duty_len_envelope_mut(&mut self) -> &mut ToneDutyLenEnvelope;
+
+  /// The channel's frequency register value.
+  fn frequency_mut(&mut self) -> &mut ToneFrequencyControl;
+
+  /// Sets the tone to the closest frequency to `hz`, and starts it.
+  ///
+  /// The channel can play from 64Hz up to 131072Hz, and values outside of
+  /// that are clamped.
+  #[inline]
+  fn play_note(&mut self, hz: u32) {
+    let rate = match hz {
+      0..=64 => 0,
+      131_072..=u32::MAX => 2047,
+      _ => 2048 - 131_072 / hz,
+    };
+    self.frequency_mut().set_frequency(rate as u16);
+    self.trigger();
+  }
+
+  /// Sets the volume the tone starts at, 0 through 15.
+  ///
+  /// This takes effect the next time the tone is triggered.
+  #[inline]
+  fn set_volume(&mut self, volume: u16) {
+    self.duty_len_envelope_mut().set_initial_volume(volume);
+  }
+
+  /// Sets the bit that starts, or restarts, the tone.
+  #[inline]
+  fn trigger(&mut self) {
+    self.frequency_mut().set_init(true);
+  }
+}
+
+/// The register values of square channel 1, `SOUND1CNT_L` through
+/// `SOUND1CNT_X`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ToneChannel1 {
+  /// The sweep register, `SOUND1CNT_L`.
+  pub sweep: ToneSweep,
+  /// The duty, length, and envelope register, `SOUND1CNT_H`.
+  pub duty_len_envelope: ToneDutyLenEnvelope,
+  /// The frequency register, `SOUND1CNT_X`.
+  pub frequency: ToneFrequencyControl,
+}
+
+impl SquareChannel for ToneChannel1 {
+  #[inline]
+  fn duty_len_envelope_mut(&mut self) -> &mut ToneDutyLenEnvelope {
+    &mut self.duty_len_envelope
+  }
+  #[inline]
+  fn frequency_mut(&mut self) -> &mut ToneFrequencyControl {
+    &mut self.frequency
+  }
+}
+
+/// The register values of square channel 2, `SOUND2CNT_L` and `SOUND2CNT_H`.
+///
+/// This is the same as channel 1 without the sweep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ToneChannel2 {
+  /// The duty, length, and envelope register, `SOUND2CNT_L`.
+  pub duty_len_envelope: ToneDutyLenEnvelope,
+  /// The frequency register, `SOUND2CNT_H`.
+  pub frequency: ToneFrequencyControl,
+}
+
+impl SquareChannel for ToneChannel2 {
+  #[inline]
+  fn duty_len_envelope_mut(&mut self) -> &mut ToneDutyLenEnvelope {
+    &mut self.duty_len_envelope
+  }
+  #[inline]
+  fn frequency_mut(&mut self) -> &mut ToneFrequencyControl {
+    &mut self.frequency
   }
 }
 
@@ -90,13 +223,13 @@ bitstruct_newtype! {
   WaveRamSelect(u8) {
     /// Set to false to use a single bank, or true to use duel banking.
     /// Only one bank will be read from at a time. This is like double buffering for video, but an audio channel instead.
-    [5: two_banks, set_two_banks],
+    [5: two_banks, set_two_banks, toggle_two_banks],
     /// Set set to false to use bank 0 for playback.
     /// Set to true to use bank 1 for playback.
-    [6: using_bank1, set_using_bank1],
+    [6: using_bank1, set_using_bank1, toggle_using_bank1],
     /// Set to true to start playback.
     /// Setting to false will interrupt and stop playback.
-    [7: playing, set_playing],
+    [7: playing, set_playing, toggle_playing],
   }
 }
 
@@ -138,10 +271,69 @@ bitstruct_newtype! {
     [0-10: sample_rate, set_sample_rate],
     /// Set to true to cause playback to stop when the sample completes playback.
     /// If set to false, samples will be looped.
-    [14: stop_at_end, set_stop_at_end],
+    [14: stop_at_end, set_stop_at_end, toggle_stop_at_end],
     /// Set to true to start playback from the beginning of the sample.
     /// Clearing to false accomplishes nothing.
-    [15: init, set_init],
+    [15: init, set_init, toggle_init],
+  }
+}
+
+/// One bank of channel 3's wave RAM: 32 4-bit samples packed into 16 bytes.
+///
+/// The samples play in order, and within each byte the high nibble plays
+/// first. With `two_banks` set, both banks play one after the other as 64
+/// samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct WaveSampleData(pub [u8; 16]);
+
+impl WaveSampleData {
+  /// Packs up to 32 4-bit samples.
+  ///
+  /// Each sample only keeps its lowest 4 bits. Extra samples past 32 are
+  /// ignored, and if there are fewer than 32 then the rest are 0.
+  #[inline]
+  #[must_use]
+  pub const fn from_samples(samples: &[u8]) -> Self {
+    let mut data = Self([0; 16]);
+    let mut i = 0;
+    while i < samples.len() && i < 32 {
+      data.set_sample(i, samples[i]);
+      i += 1;
+    }
+    data
+  }
+
+  /// The sample at `index`, 0 through 31.
+  ///
+  /// ## Panics
+  ///
+  /// If `index` is more than 31.
+  #[inline]
+  #[must_use]
+  pub const fn get_sample(&self, index: usize) -> u8 {
+    let byte = self.0[index / 2];
+    if index & 1 == 0 {
+      byte >> 4
+    } else {
+      byte & 0xF
+    }
+  }
+
+  /// Sets the sample at `index`, 0 through 31. Only the lowest 4 bits of
+  /// `value` are kept.
+  ///
+  /// ## Panics
+  ///
+  /// If `index` is more than 31.
+  #[inline]
+  pub const fn set_sample(&mut self, index: usize, value: u8) {
+    let byte = self.0[index / 2];
+    self.0[index / 2] = if index & 1 == 0 {
+      (byte & 0x0F) | (value & 0xF) << 4
+    } else {
+      (byte & 0xF0) | (value & 0xF)
+    };
   }
 }
 
@@ -155,12 +347,23 @@ bitstruct_newtype! {
     [8-10: envelope_step_time, set_envelope_step_time],
     /// Set to true for an increasing envelope.
     /// Clear to false for a decreasing envelope.
-    [11: increasing_envelope, set_increasing_envelope],
+    [11: increasing_envelope, set_increasing_envelope, toggle_increasing_envelope],
     /// The initial volume of the noise, 0 being 0% and 15 being 100%.
     [12-15: initial_volume, set_initial_volume],
   }
 }
 
+const_enum! {
+  /// The width of the noise generator's shift register (LFSR).
+  LfsrWidth(u16) {
+    /// A 15-bit register, which gives a long, hissy noise sequence.
+    Bits15(0 << 3),
+    /// A 7-bit register. The sequence repeats much sooner, which gives a more
+    /// "metallic" tone.
+    Bits7(1 << 3),
+  }
+}
+
 bitstruct_newtype! {
   /// The frequency at which the amplitude of the noise generator will be randomly changed.
   /// It is said that higher frequencies will sound "softer".
@@ -174,15 +377,34 @@ bitstruct_newtype! {
     /// Do note that if you input a value of 0 here, dividing_ratio will behave as if it is set to 0.5.
     [0-2: dividing_ratio, set_dividing_ratio],
     /// Counter step width.
-    [3: step_width_7bits, set_step_width_7bits],
+    [3: step_width_7bits, set_step_width_7bits, toggle_step_width_7bits],
     /// Shift clock frequency. See equation above.
     [4-7: shift_clock_frequency, set_shift_clock_frequency],
     /// Set to true to stop the sound when the full sound length has been played.
     /// If cleared to false, the sound will loop.
-    [14: stop_at_end, set_stop_at_end],
+    [14: stop_at_end, set_stop_at_end, toggle_stop_at_end],
     /// Set to true to start/restart playback.
     /// Setting to false will accomplish nothing.
-    [15: init, set_init],
+    [15: init, set_init, toggle_init],
+  }
+}
+
+impl NoiseFrequencyControl {
+  /// The same bit as `step_width_7bits`, as an [`LfsrWidth`].
+  #[inline]
+  #[must_use]
+  pub const fn lfsr_width(self) -> LfsrWidth {
+    if self.step_width_7bits() {
+      LfsrWidth::Bits7
+    } else {
+      LfsrWidth::Bits15
+    }
+  }
+
+  /// Sets the `step_width_7bits` bit from an [`LfsrWidth`].
+  #[inline]
+  pub const fn set_lfsr_width(&mut self, width: LfsrWidth) {
+    self.set_step_width_7bits(width.0 != 0);
   }
 }
 
@@ -202,28 +424,28 @@ bitstruct_newtype! {
   GeneratedSoundLeftRightEnabled(u8) {
     /// Set true to enable sound 1 on the right speaker.
     /// Set false to disable.
-    [0: right_sound_1_enabled, set_right_sound_1_enabled],
+    [0: right_sound_1_enabled, set_right_sound_1_enabled, toggle_right_sound_1_enabled],
     /// Set true to enable sound 2 on the right speaker.
     /// Set false to disable.
-    [1: right_sound_2_enabled, set_right_sound_2_enabled],
+    [1: right_sound_2_enabled, set_right_sound_2_enabled, toggle_right_sound_2_enabled],
     /// Set true to enable sound 3 on the right speaker.
     /// Set false to disable.
-    [2: right_sound_3_enabled, set_right_sound_3_enabled],
+    [2: right_sound_3_enabled, set_right_sound_3_enabled, toggle_right_sound_3_enabled],
     /// Set true to enable sound 4 on the right speaker.
     /// Set false to disable.
-    [3: right_sound_4_enabled, set_right_sound_4_enabled],
+    [3: right_sound_4_enabled, set_right_sound_4_enabled, toggle_right_sound_4_enabled],
     /// Set true to enable sound 1 on the left speaker.
     /// Set false to disable.
-    [4: left_sound_1_enabled, set_left_sound_1_enabled],
+    [4: left_sound_1_enabled, set_left_sound_1_enabled, toggle_left_sound_1_enabled],
     /// Set true to enable sound 2 on the left speaker.
     /// Set false to disable.
-    [5: left_sound_2_enabled, set_left_sound_2_enabled],
+    [5: left_sound_2_enabled, set_left_sound_2_enabled, toggle_left_sound_2_enabled],
     /// Set true to enable sound 3 on the left speaker.
     /// Set false to disable.
-    [6: left_sound_3_enabled, set_left_sound_3_enabled],
+    [6: left_sound_3_enabled, set_left_sound_3_enabled, toggle_left_sound_3_enabled],
     /// Set true to enable sound 4 on the left speaker.
     /// Set false to disable.
-    [7: left_sound_4_enabled, set_left_sound_4_enabled],
+    [7: left_sound_4_enabled, set_left_sound_4_enabled, toggle_left_sound_4_enabled],
   }
 }
 
@@ -245,9 +467,30 @@ bitstruct_newtype! {
     /// Sound channels 1-4 are mixed in with A and B at this volume.
     [0-1 => GeneratedSoundMixingVolume: generated_volume, set_generated_volume],
     /// Channel A volume, which 0 being 50% and 1 being 100%.
-    [2: sound_a_full, set_sound_a_full],
+    [2: sound_a_full, set_sound_a_full, toggle_sound_a_full],
     /// Channel B volume, which 0 being 50% and 1 being 100%.
-    [3: sound_b_full, set_sound_b_full],
+    [3: sound_b_full, set_sound_b_full, toggle_sound_b_full],
+  }
+}
+
+impl DmaSoundMixVolumeControl {
+  /// The overall gain of the PSG channels (sounds 1-4) on one side, as a
+  /// `(numerator, denominator)` fraction.
+  ///
+  /// The PSG output goes through two volume controls: the main volume of the
+  /// side, `main_volume` (the 0 through 7 `left` or `right` value of
+  /// [`GeneratedSoundLeftRightMainVolume`]), which scales it by
+  /// `(main_volume + 1) / 8`, and then the `generated_volume` mix here. The
+  /// denominator is always 32. The prohibited mix value is treated as 100%.
+  #[inline]
+  #[must_use]
+  pub const fn psg_gain(self, main_volume: u8) -> (u16, u16) {
+    let mix = match self.generated_volume() {
+      GeneratedSoundMixingVolume::_25 => 1,
+      GeneratedSoundMixingVolume::_50 => 2,
+      _ => 4,
+    };
+    (((main_volume & 0b111) as u16 + 1) * mix, 32)
   }
 }
 
@@ -256,28 +499,48 @@ bitstruct_newtype! {
   DmaSoundControlBits(u8) {
     /// Set true to enable channel A output on right speaker.
     /// Clear to false to disable.
-    [0: sound_a_right, set_sound_a_right],
+    [0: sound_a_right, set_sound_a_right, toggle_sound_a_right],
     /// Set true to enable channel A output on left speaker.
     /// Clear to false to disable.
-    [1: sound_a_left, set_sound_a_left],
+    [1: sound_a_left, set_sound_a_left, toggle_sound_a_left],
     /// Use to select the timer for sound channel A. Setting to 0 will select timer0.
     /// Setting to 1 will select timer 1.
-    [2: sound_a_timer1, set_sound_a_timer1],
+    [2: sound_a_timer1, set_sound_a_timer1, toggle_sound_a_timer1],
     /// Set true to reset sound fifo A.
     /// Clearing to false will do nothing.
-    [3: sound_a_fifo_reset, set_sound_a_fifo_reset],
+    [3: sound_a_fifo_reset, set_sound_a_fifo_reset, toggle_sound_a_fifo_reset],
     /// Set true to enable channel B output on right speaker.
     /// Clear to false to disable.
-    [4: sound_b_right, set_sound_b_right],
+    [4: sound_b_right, set_sound_b_right, toggle_sound_b_right],
     /// Set true to enable channel B output on left speaker.
     /// Clear to false to disable.
-    [5: sound_b_left, set_sound_b_left],
+    [5: sound_b_left, set_sound_b_left, toggle_sound_b_left],
     /// Use to select the timer for sound channel B. Setting to 0 will select timer0.
     /// Setting to 1 will select timer 1.
-    [6: sound_b_timer1, set_sound_b_timer1],
+    [6: sound_b_timer1, set_sound_b_timer1, toggle_sound_b_timer1],
     /// Set true to reset sound fifo B.
     /// Clearing to false will do nothing.
-    [7: sound_b_fifo_reset, set_sound_b_fifo_reset],
+    [7: sound_b_fifo_reset, set_sound_b_fifo_reset, toggle_sound_b_fifo_reset],
+  }
+}
+
+impl DmaSoundControlBits {
+  /// If sound A is sent to at least one speaker.
+  ///
+  /// When this is false sound A is silent no matter what's fed into it.
+  #[inline]
+  #[must_use]
+  pub const fn sound_a_routed(self) -> bool {
+    self.sound_a_left() || self.sound_a_right()
+  }
+
+  /// If sound B is sent to at least one speaker.
+  ///
+  /// When this is false sound B is silent no matter what's fed into it.
+  #[inline]
+  #[must_use]
+  pub const fn sound_b_routed(self) -> bool {
+    self.sound_b_left() || self.sound_b_right()
   }
 }
 
@@ -288,16 +551,16 @@ bitstruct_newtype! {
   /// Permits disabling all audio output.
   GeneratedSoundActiveBits(u8) {
     /// Is true when sound channel 1 is active, and false otherwise.
-    [0: sound_1_active, set_sound_1_active],
+    [0: sound_1_active, set_sound_1_active, toggle_sound_1_active],
     /// Is true when sound channel 2 is active, and false otherwise.
-    [1: sound_2_active, set_sound_2_active],
+    [1: sound_2_active, set_sound_2_active, toggle_sound_2_active],
     /// Is true when sound channel 3 is active, and false otherwise.
-    [2: sound_3_active, set_sound_3_active],
+    [2: sound_3_active, set_sound_3_active, toggle_sound_3_active],
     /// Is true when sound channel 4 is active, and false otherwise.
-    [3: sound_4_active, set_sound_4_active],
+    [3: sound_4_active, set_sound_4_active, toggle_sound_4_active],
     /// Set true to enable audio output.
     /// Set false to disable all audio output.
-    [7: sound_enabled, set_sound_enabled],
+    [7: sound_enabled, set_sound_enabled, toggle_sound_enabled],
   }
 }
 
@@ -306,7 +569,7 @@ const_enum! {
   /// Lower bit widths result in higher sample rates. This will have tradeoffs on the audio quality.
   SoundBiasSamplingSetting(u16) {
     /// Bit width of 9 with a sampling rate of 32.768kHz.
-    /// 
+    ///
     /// It is the default sampling rate.
     /// This is recommended if you wish to focus on DMA audio channels A and B.
     _9bit(0 << 14),
@@ -315,7 +578,7 @@ const_enum! {
     /// Bit width of 7 with a sampling rate of 131.072kHz.
     _7bit(2 << 14),
     /// Bit width of 6 with a sampling rate of 262.144kHz.
-    /// 
+    ///
     /// This is recommended if you wish to focus on PSG audio channels 1 through 4.
     _6bit(3 << 14),
   }
@@ -330,3 +593,132 @@ bitstruct_newtype! {
     [14-15 => SoundBiasSamplingSetting: sampling_cycle, set_sampling_cycle],
   }
 }
+
+impl SoundBiasSetting {
+  /// The bias centered in its range, with the default 9-bit sampling.
+  ///
+  /// A bias level of 0x100 puts the DC offset in the middle of the output
+  /// range, so a signal can swing equally far up and down before it clips.
+  /// This is the value the BIOS sets at boot.
+  #[inline]
+  #[must_use]
+  pub const fn centered() -> Self {
+    let mut bias = Self(0);
+    bias.set_bias_level(0x100);
+    bias.set_sampling_cycle(SoundBiasSamplingSetting::_9bit);
+    bias
+  }
+
+  /// This setting with a different bias level.
+  ///
+  /// The valid range is 0 through 0x1FF, and larger values are clamped to
+  /// 0x1FF.
+  #[inline]
+  #[must_use]
+  pub const fn with_bias_level(self, level: u16) -> Self {
+    let mut bias = self;
+    bias.set_bias_level(if level > 0x1FF { 0x1FF } else { level });
+    bias
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Register;
+
+  #[test]
+  fn dma_sound_routing() {
+    let mut bits = DmaSoundControlBits::default();
+    assert!(!bits.sound_a_routed() && !bits.sound_b_routed());
+    bits.set_sound_a_timer1(true);
+    bits.set_sound_b_fifo_reset(true);
+    assert!(!bits.sound_a_routed() && !bits.sound_b_routed());
+    bits.set_sound_a_left(true);
+    assert!(bits.sound_a_routed() && !bits.sound_b_routed());
+    bits.set_sound_b_right(true);
+    assert!(bits.sound_a_routed() && bits.sound_b_routed());
+  }
+
+  #[test]
+  fn envelope_final_volume() {
+    let mut tone = ToneDutyLenEnvelope::default();
+    tone.set_initial_volume(9);
+    assert_eq!(tone.final_volume(), 9);
+    tone.set_envelope_step_time(3);
+    assert_eq!(tone.envelope_direction(), EnvelopeDirection::Decrease);
+    assert_eq!(tone.final_volume(), 0);
+    tone.set_envelope_direction(EnvelopeDirection::Increase);
+    assert!(tone.increasing_envelope());
+    assert_eq!(tone.final_volume(), 15);
+    assert_eq!(tone.0, 9 << 12 | 1 << 11 | 3 << 8);
+    let bit11 =
+      ToneDutyLenEnvelope::FIELDS.iter().filter(|f| f.mask & 1 << 11 != 0);
+    assert_eq!(bit11.count(), 1);
+  }
+
+  #[test]
+  fn lfsr_widths() {
+    let mut noise = NoiseFrequencyControl::default();
+    assert_eq!(noise.lfsr_width(), LfsrWidth::Bits15);
+    assert!(!noise.step_width_7bits());
+    noise.set_lfsr_width(LfsrWidth::Bits7);
+    assert!(noise.step_width_7bits());
+    assert_eq!(noise.0, 1 << 3);
+    noise.set_step_width_7bits(false);
+    assert_eq!(noise.lfsr_width(), LfsrWidth::Bits15);
+    let bit3 =
+      NoiseFrequencyControl::FIELDS.iter().filter(|f| f.mask & 1 << 3 != 0);
+    assert_eq!(bit3.count(), 1);
+  }
+
+  #[test]
+  fn wave_sample_packing() {
+    let data = WaveSampleData::from_samples(&[0x1, 0x2, 0x3, 0x1F]);
+    assert_eq!(data.0[..3], [0x12, 0x3F, 0x00]);
+    assert_eq!(data.get_sample(3), 0xF);
+    let ramp: [u8; 40] = core::array::from_fn(|i| i as u8);
+    let mut data = WaveSampleData::from_samples(&ramp);
+    assert_eq!(data.get_sample(31), 15);
+    assert_eq!(data.0[15], 0xEF);
+    data.set_sample(30, 0);
+    assert_eq!(data.0[15], 0x0F);
+    assert_eq!(data.get_sample(31), 15);
+  }
+
+  #[test]
+  fn centered_bias() {
+    let bias = SoundBiasSetting::centered();
+    assert_eq!(bias.bias_level(), 0x100);
+    assert_eq!(bias.sampling_cycle(), SoundBiasSamplingSetting::_9bit);
+    assert_eq!(bias.0, 0x200);
+    assert_eq!(bias.with_bias_level(0x80).bias_level(), 0x80);
+    assert_eq!(bias.with_bias_level(0x400).bias_level(), 0x1FF);
+  }
+
+  #[test]
+  fn psg_gain_mix_settings() {
+    let mut mix = DmaSoundMixVolumeControl::default();
+    assert_eq!(mix.psg_gain(7), (8, 32));
+    mix.set_generated_volume(GeneratedSoundMixingVolume::_50);
+    assert_eq!(mix.psg_gain(7), (16, 32));
+    mix.set_generated_volume(GeneratedSoundMixingVolume::_100);
+    assert_eq!(mix.psg_gain(7), (32, 32));
+    assert_eq!(mix.psg_gain(3), (16, 32));
+    assert_eq!(DmaSoundMixVolumeControl(3).psg_gain(0), (4, 32));
+  }
+
+  #[test]
+  fn play_note_on_channel_2() {
+    let mut channel = ToneChannel2::default();
+    channel.set_volume(12);
+    channel.play_note(440);
+    assert_eq!(channel.frequency.frequency(), 1751);
+    assert!(channel.frequency.init());
+    assert_eq!(channel.duty_len_envelope.initial_volume(), 12);
+    channel.play_note(10);
+    assert_eq!(channel.frequency.frequency(), 0);
+    channel.play_note(200_000);
+    assert_eq!(channel.frequency.frequency(), 2047);
+  }
+}
@@ -1,12 +1,32 @@
 //! There are 4 timers in the GBA, each one has a control register and a
 //! counter register.
 //!
-//! The counter registers are simply u16 fields, so we do not bother to create
-//! a special representation of them. Reading from these counter registers will
-//! give you the current count of how many cycles the base frequency has
-//! cycled. Writing to this register will not immediately change its value, but
-//! rather will set the value to be loaded into it the next time the timer is
-//! enabled/re-enabled.
+//! The counter registers are simply u16 fields. Reading from these counter
+//! registers will give you the current count of how many cycles the base
+//! frequency has cycled. Writing to this register will not immediately change
+//! its value, but rather will set the value to be loaded into it the next time
+//! the timer is enabled/re-enabled. The [`TimerReload`] type wraps that written
+//! value for use with the timing helpers here.
+
+use crate::InterruptFlagBits;
+use core::convert::TryFrom;
+
+/// The CPU clock rate, which is the base rate that all timers count at.
+const CPU_HZ: u32 = 16_777_216;
+
+const_enum! {
+  /// One of the four timers.
+  TimerIndex(u8) {
+    /// Timer 0.
+    Timer0(0),
+    /// Timer 1.
+    Timer1(1),
+    /// Timer 2.
+    Timer2(2),
+    /// Timer 3.
+    Timer3(3),
+  }
+}
 
 const_enum! {
   /// A scale factor that sets the base frequency of the timer.
@@ -29,10 +49,221 @@ bitstruct_newtype! {
     [0-1 => TimerScaleFactor: scale_factor, set_scale_factor],
     /// Instead of incrementing the count with each pre-scaled clock cycle, will instead increment whenever the lower number timer overflows.
     /// Timer0 cannot make effective use of this feature because there is no lower number timer than it.
-    [2: overflow_counting, set_overflow_counting],
+    [2: overflow_counting, set_overflow_counting, toggle_overflow_counting],
     /// Set to 1 to generate an interrupt when this timer overflows.
-    [6: interrupt_on_overflow, set_interrupt_on_overflow],
+    [6: interrupt_on_overflow, set_interrupt_on_overflow, toggle_interrupt_on_overflow],
     /// Set to 1 to enable the timer. Will clear the timer when enabled.
-    [7: enabled, set_enabled],
+    [7: enabled, set_enabled, toggle_enabled],
+  }
+}
+
+bitstruct_newtype! {
+  /// The value written to a timer's counter register.
+  ///
+  /// The timer starts counting from this value, and overflows after it passes
+  /// `0xFFFF`. On overflow it goes back to this value again.
+  TimerReload(u16) {
+    /// The value the counter is reloaded with.
+    [0-15: reload, set_reload],
+  }
+}
+
+impl TimerReload {
+  /// How many ticks the timer counts between each overflow.
+  #[inline]
+  #[must_use]
+  pub const fn ticks_per_overflow(self) -> u32 {
+    0x1_0000 - self.0 as u32
+  }
+}
+
+/// The number of CPU cycles in one tick of the given scale factor.
+const fn cycles_per_tick(scale: TimerScaleFactor) -> u32 {
+  match scale {
+    TimerScaleFactor::_1 => 1,
+    TimerScaleFactor::_64 => 64,
+    TimerScaleFactor::_256 => 256,
+    _ => 1024,
+  }
+}
+
+impl TimerScaleFactor {
+  /// The rate that a timer with this scale factor ticks at, in Hz.
+  #[inline]
+  #[must_use]
+  pub const fn output_hz(self) -> u32 {
+    CPU_HZ / cycles_per_tick(self)
+  }
+}
+
+/// The `IF` bit that a timer sets when it overflows.
+///
+/// The timer only raises this interrupt if it has `interrupt_on_overflow` set.
+#[inline]
+#[must_use]
+pub const fn overflow_interrupt(timer: TimerIndex) -> InterruptFlagBits {
+  let mut bits = InterruptFlagBits(0);
+  match timer {
+    TimerIndex::Timer0 => bits.set_timer0(true),
+    TimerIndex::Timer1 => bits.set_timer1(true),
+    TimerIndex::Timer2 => bits.set_timer2(true),
+    _ => bits.set_timer3(true),
+  }
+  bits
+}
+
+/// How many times per second a timer overflows, given its reload value and
+/// scale factor.
+///
+/// This is rounded down to a whole number of Hz.
+#[inline]
+#[must_use]
+pub const fn overflow_hz(reload: TimerReload, scale: TimerScaleFactor) -> u32 {
+  scale.output_hz() / reload.ticks_per_overflow()
+}
+
+/// Picks the finest scale factor that can overflow once every `period_ns`
+/// nanoseconds, along with the reload value to use.
+///
+/// The number of ticks is rounded to the nearest whole tick. Returns `None`
+/// if the period rounds to zero ticks even at the finest scale, or needs more
+/// than `0x10000` ticks even at the coarsest scale.
+#[inline]
+#[must_use]
+pub const fn best_for_period_ns(
+  period_ns: u64,
+) -> Option<(TimerScaleFactor, u16)> {
+  let mut i = 0;
+  while i < TimerScaleFactor::ALL.len() {
+    let scale = TimerScaleFactor::ALL[i];
+    let denominator = cycles_per_tick(scale) as u128 * 1_000_000_000;
+    let ticks =
+      (period_ns as u128 * CPU_HZ as u128 + denominator / 2) / denominator;
+    if ticks == 0 {
+      return None;
+    }
+    if ticks <= 0x1_0000 {
+      return Some((scale, (0x1_0000 - ticks) as u16));
+    }
+    i += 1;
+  }
+  None
+}
+
+/// The overflow period of a chain of cascaded timers, in nanoseconds.
+///
+/// The timers are given in order starting from the lowest numbered. The first
+/// timer counts at its scale factor, and every other timer must have
+/// `overflow_counting` set so that it counts overflows of the timer before it.
+/// The period is how often the last timer in the chain overflows.
+///
+/// Returns `None` if the chain is empty, isn't set up to cascade as described
+/// above, or if the period doesn't fit in a `u64`. This is an `Option` rather
+/// than a plain `u64` so that an invalid chain can't be mistaken for a real
+/// period.
+#[must_use]
+pub fn cascade_chain_period_ns(
+  timers: &[(TimerControlSetting, TimerReload)],
+) -> Option<u64> {
+  let ((first, _), rest) = timers.split_first()?;
+  if first.overflow_counting()
+    || rest.iter().any(|(c, _)| !c.overflow_counting())
+  {
+    return None;
+  }
+  let cycles = timers.iter().fold(
+    u128::from(cycles_per_tick(first.scale_factor())),
+    |cycles, (_, reload)| cycles * u128::from(reload.ticks_per_overflow()),
+  );
+  u64::try_from(cycles * 1_000_000_000 / u128::from(CPU_HZ)).ok()
+}
+
+/// The counter and control register offsets of a timer, in that order.
+///
+/// Each timer's registers take 4 bytes, starting with timer 0 at 0x100.
+#[inline]
+#[must_use]
+pub const fn timer_register_offsets(timer: TimerIndex) -> (usize, usize) {
+  let counter = 0x100 + 4 * (timer.0 as usize & 0b11);
+  (counter, counter + 2)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cascade_chain_of_two_timers() {
+    let mut first = TimerControlSetting::default();
+    first.set_scale_factor(TimerScaleFactor::_1024);
+    let mut second = TimerControlSetting::default();
+    second.set_overflow_counting(true);
+    // 16 ticks of 1024 cycles, then 1024 overflows of that: exactly 1 second.
+    let chain = [(first, TimerReload(0xFFF0)), (second, TimerReload(0xFC00))];
+    assert_eq!(cascade_chain_period_ns(&chain), Some(1_000_000_000));
+    assert_eq!(cascade_chain_period_ns(&chain[..1]), Some(976_562));
+    assert_eq!(cascade_chain_period_ns(&[]), None);
+    assert_eq!(cascade_chain_period_ns(&[chain[1], chain[1]]), None);
+    assert_eq!(cascade_chain_period_ns(&[chain[0], chain[0]]), None);
+  }
+
+  #[test]
+  fn scale_factor_saturates_at_both_ends() {
+    assert_eq!(TimerScaleFactor::_1.saturating_next(), TimerScaleFactor::_64);
+    assert_eq!(
+      TimerScaleFactor::_1024.saturating_next(),
+      TimerScaleFactor::_1024
+    );
+    assert_eq!(TimerScaleFactor::_1.saturating_prev(), TimerScaleFactor::_1);
+    assert_eq!(
+      TimerScaleFactor::_1024.saturating_prev(),
+      TimerScaleFactor::_256
+    );
+  }
+
+  #[test]
+  fn one_khz_overflow() {
+    assert_eq!(TimerScaleFactor::_64.output_hz(), 262_144);
+    let reload = TimerReload(0xFEFA);
+    assert_eq!(reload.ticks_per_overflow(), 262);
+    assert_eq!(overflow_hz(reload, TimerScaleFactor::_64), 1000);
+    assert_eq!(overflow_hz(TimerReload(0xFFF0), TimerScaleFactor::_1024), 1024);
+    assert_eq!(overflow_hz(TimerReload(0), TimerScaleFactor::_1), 256);
+  }
+
+  #[test]
+  fn best_scale_for_periods() {
+    assert_eq!(
+      best_for_period_ns(100_000),
+      Some((TimerScaleFactor::_1, 0xF972))
+    );
+    assert_eq!(
+      best_for_period_ns(2_000_000_000),
+      Some((TimerScaleFactor::_1024, 0x8000))
+    );
+    assert_eq!(
+      best_for_period_ns(4_000_000_000),
+      Some((TimerScaleFactor::_1024, 0))
+    );
+    assert_eq!(best_for_period_ns(5_000_000_000), None);
+    assert_eq!(best_for_period_ns(0), None);
+  }
+
+  #[test]
+  fn overflow_interrupt_bits() {
+    assert_eq!(overflow_interrupt(TimerIndex::Timer0).0, 1 << 3);
+    assert_eq!(overflow_interrupt(TimerIndex::Timer1).0, 1 << 4);
+    assert_eq!(overflow_interrupt(TimerIndex::Timer2).0, 1 << 5);
+    let timer3 = overflow_interrupt(TimerIndex::Timer3);
+    assert!(timer3.timer3());
+    assert_eq!(timer3.0, 1 << 6);
+  }
+
+  #[test]
+  fn timer_offsets() {
+    assert_eq!(timer_register_offsets(TimerIndex::Timer0), (0x100, 0x102));
+    assert_eq!(timer_register_offsets(TimerIndex::Timer1), (0x104, 0x106));
+    assert_eq!(timer_register_offsets(TimerIndex::Timer2), (0x108, 0x10A));
+    assert_eq!(timer_register_offsets(TimerIndex::Timer3), (0x10C, 0x10E));
   }
 }